@@ -183,6 +183,22 @@ fn compact_string_from_string_heap_long(c: &mut Criterion) {
     });
 }
 
+fn compact_string_repeat_single_byte(c: &mut Criterion) {
+    // Repeating a single byte takes a fast path that gets optimized into a `memset`
+    let compact = CompactString::new("a");
+    c.bench_function("repeat single byte, 1M", |b| {
+        b.iter(|| black_box(compact.repeat(black_box(1_000_000))))
+    });
+}
+
+fn compact_string_repeat_multi_byte(c: &mut Criterion) {
+    // ...while longer strings are copied `n` times, for comparison
+    let compact = CompactString::new("ab");
+    c.bench_function("repeat multi byte, 1M", |b| {
+        b.iter(|| black_box(compact.repeat(black_box(500_000))))
+    });
+}
+
 fn std_string_short_length(c: &mut Criterion) {
     let word = "i am short";
     let string = String::from(word);
@@ -292,7 +308,9 @@ criterion_group!(
     compact_string_extend_chars_heap_20,
     compact_string_from_string_inline,
     compact_string_from_string_heap,
-    compact_string_from_string_heap_long
+    compact_string_from_string_heap_long,
+    compact_string_repeat_single_byte,
+    compact_string_repeat_multi_byte,
 );
 criterion_group!(
     std_string,
@@ -0,0 +1,122 @@
+use core::borrow::Borrow;
+use core::hash::{
+    Hash,
+    Hasher,
+};
+
+use crate::CompactString;
+
+/// A wrapper around a [`CompactString`] that can be looked up by a byte slice, e.g. in a
+/// `HashMap<BytesKey, V>` that gets queried with `&[u8]` keys.
+///
+/// [`CompactString`] implements `Borrow<str>`, so it must hash exactly the same as a [`str`]. A
+/// [`str`] hashes differently than the equivalent `[u8]`, so [`CompactString`] can't also
+/// implement `Borrow<[u8]>`. [`BytesKey`] hashes the same as a `[u8]` instead.
+///
+/// # Examples
+/// ```
+/// use std::collections::HashMap;
+/// use compact_str::{BytesKey, CompactString};
+///
+/// let mut headers = HashMap::new();
+/// headers.insert(BytesKey(CompactString::new("content-type")), "text/plain");
+///
+/// let name: &[u8] = b"content-type";
+/// assert_eq!(headers.get(name), Some(&"text/plain"));
+/// ```
+#[derive(Clone, Debug, Default, PartialEq, Eq, PartialOrd, Ord)]
+pub struct BytesKey(pub CompactString);
+
+impl Hash for BytesKey {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.0.as_bytes().hash(state)
+    }
+}
+
+impl Borrow<[u8]> for BytesKey {
+    fn borrow(&self) -> &[u8] {
+        self.0.as_bytes()
+    }
+}
+
+impl AsRef<[u8]> for BytesKey {
+    fn as_ref(&self) -> &[u8] {
+        self.0.as_bytes()
+    }
+}
+
+impl From<CompactString> for BytesKey {
+    fn from(s: CompactString) -> Self {
+        BytesKey(s)
+    }
+}
+
+impl From<BytesKey> for CompactString {
+    fn from(key: BytesKey) -> Self {
+        key.0
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use alloc::collections::BTreeMap;
+    use alloc::vec::Vec;
+    use core::hash::{
+        BuildHasher,
+        Hash,
+        Hasher,
+    };
+    use std::collections::hash_map::RandomState;
+    use std::collections::HashMap;
+
+    use super::BytesKey;
+    use crate::CompactString;
+
+    #[test]
+    fn test_hash_matches_bytes() {
+        // `BuildHasher::hash_one` needs Rust 1.71, above our MSRV
+        #[allow(clippy::manual_hash_one)]
+        fn hash(state: &RandomState, value: &(impl Hash + ?Sized)) -> u64 {
+            let mut hasher = state.build_hasher();
+            value.hash(&mut hasher);
+            hasher.finish()
+        }
+
+        let state = RandomState::new();
+        for s in ["", "short", "a string that is long enough to be heap allocated", "🦀"] {
+            let key = BytesKey(CompactString::new(s));
+            assert_eq!(hash(&state, &key), hash(&state, s.as_bytes()));
+        }
+    }
+
+    #[test]
+    fn test_lookup_by_bytes() {
+        let mut map = HashMap::new();
+        map.insert(BytesKey(CompactString::new("short")), 1);
+        map.insert(BytesKey(CompactString::new("a much longer key that lives on the heap")), 2);
+        map.insert(BytesKey(CompactString::new("grüße")), 3);
+
+        assert_eq!(map.get(&b"short"[..]), Some(&1));
+        assert_eq!(map.get(&b"a much longer key that lives on the heap"[..]), Some(&2));
+        assert_eq!(map.get("grüße".as_bytes()), Some(&3));
+        assert_eq!(map.get(&b"missing"[..]), None);
+        assert_eq!(map.get(&b"grus\xC3"[..]), None);
+    }
+
+    #[test]
+    fn test_ordered_lookup_by_bytes() {
+        let mut map = BTreeMap::new();
+        for (i, s) in ["b", "a", "🦀", "ab", ""].iter().enumerate() {
+            map.insert(BytesKey(CompactString::new(s)), i);
+        }
+
+        // ordering matches the ordering of the bytes
+        let keys: Vec<&[u8]> = map.keys().map(|k| k.as_ref()).collect();
+        let mut expected: Vec<&[u8]> = vec![b"", b"a", b"ab", b"b", "🦀".as_bytes()];
+        expected.sort();
+        assert_eq!(keys, expected);
+
+        assert_eq!(map.get(&b"ab"[..]), Some(&3));
+        assert_eq!(map.get("🦀".as_bytes()), Some(&2));
+    }
+}
@@ -0,0 +1,84 @@
+use crate::CompactString;
+
+/// Describes which internal representation backs a [`CompactString`].
+///
+/// Returned by [`CompactString::repr_kind()`].
+#[cfg_attr(docsrs, doc(cfg(feature = "debug-repr")))]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum ReprKind {
+    /// The string is stored inline, without any heap allocation
+    Inline,
+    /// The string is stored in a buffer on the heap
+    Heap,
+    /// The string is a reference to a `&'static str`, see [`CompactString::from_static_str()`]
+    StaticStr,
+}
+
+impl CompactString {
+    /// Returns which internal representation backs this [`CompactString`].
+    ///
+    /// This is more specific than [`CompactString::is_heap_allocated()`], and is meant for tests
+    /// that assert a certain optimization happened.
+    ///
+    /// # Example
+    /// ```
+    /// use compact_str::{CompactString, ReprKind};
+    ///
+    /// assert_eq!(CompactString::new("hello").repr_kind(), ReprKind::Inline);
+    ///
+    /// let long = "this is a long string that can't be stored inline";
+    /// assert_eq!(CompactString::new(long).repr_kind(), ReprKind::Heap);
+    /// assert_eq!(CompactString::from_static_str(long).repr_kind(), ReprKind::StaticStr);
+    /// ```
+    #[cfg_attr(docsrs, doc(cfg(feature = "debug-repr")))]
+    #[inline]
+    pub fn repr_kind(&self) -> ReprKind {
+        if self.as_static_str().is_some() {
+            ReprKind::StaticStr
+        } else if self.is_heap_allocated() {
+            ReprKind::Heap
+        } else {
+            ReprKind::Inline
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use alloc::string::String;
+
+    use super::ReprKind;
+    use crate::CompactString;
+
+    const LONG: &str = "this is a long string that can't be stored inline";
+
+    #[test]
+    fn test_inline() {
+        assert_eq!(CompactString::new("").repr_kind(), ReprKind::Inline);
+        assert_eq!(CompactString::new("hello").repr_kind(), ReprKind::Inline);
+        // short `&'static str`s get inlined
+        assert_eq!(CompactString::from_static_str("hello").repr_kind(), ReprKind::Inline);
+    }
+
+    #[test]
+    fn test_static_str() {
+        const COMPACT: CompactString = CompactString::from_static_str(LONG);
+        assert_eq!(COMPACT.repr_kind(), ReprKind::StaticStr);
+
+        // mutating a `&'static str` copies it
+        let mut compact = COMPACT;
+        compact.push('!');
+        assert_eq!(compact.repr_kind(), ReprKind::Heap);
+    }
+
+    #[test]
+    fn test_heap() {
+        let compact = CompactString::from(String::from(LONG));
+        assert_eq!(compact.repr_kind(), ReprKind::Heap);
+
+        // reserving moves inline strings onto the heap
+        let mut compact = CompactString::new("hello");
+        compact.reserve(100);
+        assert_eq!(compact.repr_kind(), ReprKind::Heap);
+    }
+}
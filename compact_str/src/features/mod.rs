@@ -4,6 +4,8 @@
 mod arbitrary;
 #[cfg(feature = "bytes")]
 mod bytes;
+#[cfg(feature = "debug-repr")]
+mod debug_repr;
 #[cfg(feature = "diesel")]
 mod diesel;
 #[cfg(feature = "markup")]
@@ -12,9 +14,18 @@ mod markup;
 mod proptest;
 #[cfg(feature = "quickcheck")]
 mod quickcheck;
+#[cfg(feature = "rayon")]
+mod rayon;
 #[cfg(feature = "rkyv")]
 mod rkyv;
 #[cfg(feature = "serde")]
 mod serde;
 #[cfg(feature = "smallvec")]
 mod smallvec;
+#[cfg(feature = "unicode-normalization")]
+mod unicode_normalization;
+#[cfg(feature = "unicode-width")]
+mod unicode_width;
+
+#[cfg(feature = "debug-repr")]
+pub use debug_repr::ReprKind;
@@ -0,0 +1,136 @@
+use alloc::collections::LinkedList;
+use alloc::string::String;
+
+use rayon::iter::{
+    FromParallelIterator,
+    IntoParallelIterator,
+    ParallelExtend,
+    ParallelIterator,
+};
+
+use crate::CompactString;
+
+/// Collects the items of `par_iter` into a [`CompactString`] per split of the work, keeping the
+/// buffers in the order of the items they contain
+fn collect_buffers<I, F>(par_iter: I, push: F) -> LinkedList<CompactString>
+where
+    I: IntoParallelIterator,
+    F: Fn(&mut CompactString, I::Item) + Sync + Send,
+{
+    par_iter
+        .into_par_iter()
+        .fold(CompactString::default, |mut buf, item| {
+            push(&mut buf, item);
+            buf
+        })
+        .map(|buf| {
+            let mut list = LinkedList::new();
+            list.push_back(buf);
+            list
+        })
+        .reduce(LinkedList::new, |mut a, mut b| {
+            a.append(&mut b);
+            a
+        })
+}
+
+/// Appends all of the `buffers` to `this`, reserving their combined length up front
+fn extend_buffers(this: &mut CompactString, buffers: LinkedList<CompactString>) {
+    let len = buffers.iter().map(CompactString::len).sum();
+    this.reserve(len);
+
+    for buf in buffers {
+        this.push_str(&buf);
+    }
+}
+
+/// Implements [`ParallelExtend`] and [`FromParallelIterator`] for [`CompactString`], with the
+/// provided function appending a single item to a buffer
+macro_rules! impl_par_extend {
+    (impl$(<$lt:lifetime>)? for $t:ty, $push:expr) => {
+        #[cfg_attr(docsrs, doc(cfg(feature = "rayon")))]
+        impl$(<$lt>)? ParallelExtend<$t> for CompactString {
+            fn par_extend<I: IntoParallelIterator<Item = $t>>(&mut self, par_iter: I) {
+                let buffers = collect_buffers(par_iter, $push);
+                extend_buffers(self, buffers);
+            }
+        }
+
+        #[cfg_attr(docsrs, doc(cfg(feature = "rayon")))]
+        impl$(<$lt>)? FromParallelIterator<$t> for CompactString {
+            fn from_par_iter<I: IntoParallelIterator<Item = $t>>(par_iter: I) -> Self {
+                let mut compact = CompactString::default();
+                compact.par_extend(par_iter);
+                compact
+            }
+        }
+    };
+}
+
+impl_par_extend!(impl for char, |buf: &mut CompactString, c| buf.push(c));
+impl_par_extend!(impl<'a> for &'a char, |buf: &mut CompactString, c| buf.push(*c));
+impl_par_extend!(impl<'a> for &'a str, |buf: &mut CompactString, s| buf.push_str(s));
+impl_par_extend!(impl for String, |buf: &mut CompactString, s| buf.push_str(&s));
+impl_par_extend!(impl for CompactString, |buf: &mut CompactString, s| buf.push_str(&s));
+
+#[cfg(test)]
+mod tests {
+    use alloc::string::String;
+    use alloc::vec::Vec;
+
+    use rayon::prelude::*;
+
+    use crate::CompactString;
+
+    fn segments() -> Vec<String> {
+        (0..10_000).map(|i| format!("{}🦀ü,", i)).collect()
+    }
+
+    #[test]
+    #[cfg_attr(miri, ignore)]
+    fn test_from_par_iter_str() {
+        let segments = segments();
+        let sequential: String = segments.iter().map(String::as_str).collect();
+
+        let compact: CompactString = segments.par_iter().map(String::as_str).collect();
+        assert_eq!(compact, sequential);
+
+        let compact: CompactString = segments.clone().into_par_iter().collect();
+        assert_eq!(compact, sequential);
+
+        let compact: CompactString = segments.par_iter().map(CompactString::new).collect();
+        assert_eq!(compact, sequential);
+    }
+
+    #[test]
+    #[cfg_attr(miri, ignore)]
+    fn test_from_par_iter_char() {
+        let sequential: String = segments().concat();
+        let chars: Vec<char> = sequential.chars().collect();
+
+        let compact: CompactString = chars.par_iter().collect();
+        assert_eq!(compact, sequential);
+
+        let compact: CompactString = chars.into_par_iter().collect();
+        assert_eq!(compact, sequential);
+    }
+
+    #[test]
+    fn test_par_extend() {
+        let mut compact = CompactString::new("hello");
+        compact.par_extend(vec![" ", "world", "!"]);
+        assert_eq!(compact, "hello world!");
+        assert!(!compact.is_heap_allocated());
+
+        compact.par_extend(" this string is now heap allocated".par_chars());
+        assert_eq!(compact, "hello world! this string is now heap allocated");
+        assert!(compact.is_heap_allocated());
+    }
+
+    #[test]
+    fn test_from_par_iter_empty() {
+        let compact: CompactString = Vec::<&str>::new().into_par_iter().collect();
+        assert_eq!(compact, "");
+        assert!(!compact.is_heap_allocated());
+    }
+}
@@ -137,6 +137,32 @@ mod tests {
         assert_eq!(compact_de_std, compact);
     }
 
+    #[derive(Debug, PartialEq, Eq, Deserialize)]
+    struct Borrowed<'a> {
+        #[serde(borrow)]
+        tag: &'a str,
+        words: Vec<CompactString>,
+        escaped: CompactString,
+    }
+
+    #[test]
+    fn test_deserialize_borrowed_input() {
+        let words: Vec<String> = (0..256).map(|i| format!("word #{}", i)).collect();
+        let json = format!(
+            r#"{{"tag":"many short strings","words":{},"escaped":"tab\tnewline\n"}}"#,
+            serde_json::to_string(&words).unwrap(),
+        );
+
+        // `tag` borrows from the input, while the `CompactString`s don't need to
+        let borrowed: Borrowed<'_> = serde_json::from_str(&json).unwrap();
+        assert_eq!(borrowed.tag, "many short strings");
+        assert_eq!(borrowed.words, words);
+        assert!(borrowed.words.iter().all(|word| !word.is_heap_allocated()));
+
+        // strings with escapes can't be borrowed, and get deserialized from a temporary instead
+        assert_eq!(borrowed.escaped, "tab\tnewline\n");
+    }
+
     #[cfg_attr(miri, ignore)]
     #[proptest]
     fn proptest_roundtrip(name: String, phones: Vec<String>, address: Option<String>) {
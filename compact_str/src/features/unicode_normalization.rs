@@ -0,0 +1,104 @@
+use unicode_normalization::UnicodeNormalization;
+
+use crate::CompactString;
+
+impl CompactString {
+    /// Returns a copy of the [`CompactString`] in Unicode Normalization Form C, i.e. canonically
+    /// composed.
+    ///
+    /// Note: The result is collected into a new [`CompactString`], so short strings are stored
+    /// inline and never heap allocate.
+    ///
+    /// # Example
+    /// ```
+    /// use compact_str::CompactString;
+    ///
+    /// // "e" followed by a combining acute accent
+    /// let decomposed = CompactString::new("cafe\u{301}");
+    /// let composed = decomposed.to_nfc();
+    ///
+    /// assert_eq!(composed, "caf\u{e9}");
+    /// assert!(composed.is_nfc());
+    /// ```
+    #[cfg_attr(docsrs, doc(cfg(feature = "unicode-normalization")))]
+    pub fn to_nfc(&self) -> CompactString {
+        self.as_str().nfc().collect()
+    }
+
+    /// Returns a copy of the [`CompactString`] in Unicode Normalization Form D, i.e. canonically
+    /// decomposed.
+    ///
+    /// # Example
+    /// ```
+    /// use compact_str::CompactString;
+    ///
+    /// let composed = CompactString::new("caf\u{e9}");
+    /// let decomposed = composed.to_nfd();
+    ///
+    /// assert_eq!(decomposed, "cafe\u{301}");
+    /// assert!(decomposed.is_nfd());
+    /// ```
+    #[cfg_attr(docsrs, doc(cfg(feature = "unicode-normalization")))]
+    pub fn to_nfd(&self) -> CompactString {
+        self.as_str().nfd().collect()
+    }
+
+    /// Returns `true` if the [`CompactString`] is already in Unicode Normalization Form C.
+    #[cfg_attr(docsrs, doc(cfg(feature = "unicode-normalization")))]
+    pub fn is_nfc(&self) -> bool {
+        unicode_normalization::is_nfc(self.as_str())
+    }
+
+    /// Returns `true` if the [`CompactString`] is already in Unicode Normalization Form D.
+    #[cfg_attr(docsrs, doc(cfg(feature = "unicode-normalization")))]
+    pub fn is_nfd(&self) -> bool {
+        unicode_normalization::is_nfd(self.as_str())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use test_case::test_case;
+
+    use crate::repr::MAX_SIZE;
+    use crate::CompactString;
+
+    #[test_case("hello world" ; "ascii")]
+    #[test_case("this ascii string is long enough to be heap allocated" ; "ascii heap")]
+    #[test_case("" ; "empty")]
+    fn test_ascii_unchanged(text: &str) {
+        let compact = CompactString::new(text);
+        assert!(compact.is_nfc());
+        assert!(compact.is_nfd());
+
+        assert_eq!(compact.to_nfc(), text);
+        assert_eq!(compact.to_nfd(), text);
+        assert_eq!(compact.to_nfc().is_heap_allocated(), text.len() > MAX_SIZE);
+    }
+
+    #[test]
+    fn test_combining_acute() {
+        let decomposed = CompactString::new("e\u{301}");
+        assert!(!decomposed.is_nfc());
+        assert!(decomposed.is_nfd());
+
+        let composed = decomposed.to_nfc();
+        assert_eq!(composed, "\u{e9}");
+        assert!(composed.is_nfc());
+        assert!(!composed.is_nfd());
+        assert!(!composed.is_heap_allocated());
+
+        assert_eq!(composed.to_nfd(), decomposed);
+    }
+
+    #[test]
+    fn test_roundtrip_heap() {
+        let text = "Cr\u{e8}me br\u{fb}l\u{e9}e, na\u{ef}ve r\u{e9}sum\u{e9}";
+        let composed = CompactString::new(text);
+        assert!(composed.is_heap_allocated());
+
+        let decomposed = composed.to_nfd();
+        assert_eq!(decomposed.chars().count(), composed.chars().count() + 6);
+        assert_eq!(decomposed.to_nfc(), composed);
+    }
+}
@@ -0,0 +1,121 @@
+use unicode_width::UnicodeWidthChar;
+
+use crate::CompactString;
+
+impl CompactString {
+    /// Returns a copy of the [`CompactString`] truncated so that its displayed width, e.g. the
+    /// number of columns it occupies in a terminal, does not exceed `max_width`.
+    ///
+    /// Wide characters, like CJK ideographs, count as two columns and zero-width characters, like
+    /// combining marks, count as zero columns. The string is only ever truncated on a `char`
+    /// boundary, so a wide character that would straddle `max_width` gets dropped entirely.
+    ///
+    /// Note: The result is a new [`CompactString`], so short strings are stored inline and never
+    /// heap allocate.
+    ///
+    /// # Example
+    /// ```
+    /// use compact_str::CompactString;
+    ///
+    /// let ascii = CompactString::new("hello world");
+    /// assert_eq!(ascii.truncate_to_width(5), "hello");
+    ///
+    /// // each of these characters is two columns wide
+    /// let cjk = CompactString::new("日本語");
+    /// assert_eq!(cjk.truncate_to_width(5), "日本");
+    ///
+    /// // "e" followed by a combining acute accent has a width of one
+    /// let combining = CompactString::new("cafe\u{301}s");
+    /// assert_eq!(combining.truncate_to_width(4), "cafe\u{301}");
+    /// ```
+    #[cfg_attr(docsrs, doc(cfg(feature = "unicode-width")))]
+    pub fn truncate_to_width(&self, max_width: usize) -> CompactString {
+        let mut width = 0;
+        let end = self
+            .char_indices()
+            .find_map(|(idx, c)| {
+                width += c.width().unwrap_or(0);
+                if width > max_width {
+                    Some(idx)
+                } else {
+                    None
+                }
+            })
+            .unwrap_or(self.len());
+
+        CompactString::new(&self[..end])
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use test_case::test_case;
+    use unicode_width::UnicodeWidthStr;
+
+    use crate::repr::MAX_SIZE;
+    use crate::CompactString;
+
+    #[test_case("hello world", 5, "hello" ; "ascii")]
+    #[test_case("hello world", 11, "hello world" ; "ascii exact")]
+    #[test_case("hello world", 100, "hello world" ; "ascii wider")]
+    #[test_case("hello world", 0, "" ; "ascii zero")]
+    #[test_case("", 10, "" ; "empty")]
+    fn test_ascii(text: &str, max_width: usize, expected: &str) {
+        let compact = CompactString::new(text);
+        let truncated = compact.truncate_to_width(max_width);
+
+        assert_eq!(truncated, expected);
+        // for ASCII the width is the number of chars
+        assert_eq!(truncated.width(), truncated.chars().count());
+        assert!(truncated.width() <= max_width);
+    }
+
+    #[test_case(0, "" ; "zero")]
+    #[test_case(1, "" ; "one")]
+    #[test_case(2, "日" ; "two")]
+    #[test_case(5, "日本" ; "odd")]
+    #[test_case(6, "日本語" ; "exact")]
+    fn test_cjk(max_width: usize, expected: &str) {
+        let compact = CompactString::new("日本語");
+        assert_eq!(compact.width(), 6);
+
+        let truncated = compact.truncate_to_width(max_width);
+        assert_eq!(truncated, expected);
+        // each char is two columns wide
+        assert_eq!(truncated.width(), truncated.chars().count() * 2);
+        assert!(truncated.width() <= max_width);
+    }
+
+    #[test_case(0, "" ; "zero")]
+    #[test_case(1, "e\u{301}" ; "keeps combining mark")]
+    #[test_case(2, "e\u{301}a\u{300}\u{302}" ; "keeps multiple combining marks")]
+    #[test_case(3, "e\u{301}a\u{300}\u{302}o" ; "all")]
+    fn test_combining_marks(max_width: usize, expected: &str) {
+        let compact = CompactString::new("e\u{301}a\u{300}\u{302}o");
+        assert_eq!(compact.width(), 3);
+
+        let truncated = compact.truncate_to_width(max_width);
+        assert_eq!(truncated, expected);
+        assert!(truncated.width() <= max_width);
+    }
+
+    #[test]
+    fn test_heap_to_inline() {
+        let compact = CompactString::new("this string is long enough to be heap allocated");
+        assert!(compact.is_heap_allocated());
+
+        let truncated = compact.truncate_to_width(MAX_SIZE);
+        assert_eq!(truncated, &compact[..MAX_SIZE]);
+        assert!(!truncated.is_heap_allocated());
+    }
+
+    #[test]
+    fn test_mixed_widths() {
+        let compact = CompactString::new("id: 日本語 cafe\u{301}");
+        for max_width in 0..=compact.width() + 1 {
+            let truncated = compact.truncate_to_width(max_width);
+            assert!(truncated.width() <= max_width);
+            assert!(compact.starts_with(truncated.as_str()));
+        }
+    }
+}
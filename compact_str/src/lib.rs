@@ -47,12 +47,20 @@ use core::{
 #[cfg(feature = "std")]
 use std::ffi::OsStr;
 
+mod bytes_key;
+pub use bytes_key::BytesKey;
 mod features;
+#[cfg(feature = "debug-repr")]
+pub use features::ReprKind;
 mod macros;
+pub use macros::format_compact;
 mod unicode_data;
 
 mod repr;
-use repr::Repr;
+use repr::{
+    IntoRepr,
+    Repr,
+};
 
 mod traits;
 pub use traits::{
@@ -210,6 +218,15 @@ impl CompactString {
         CompactString(Repr::new(text.as_ref()).unwrap_with_msg())
     }
 
+    /// Fallible version of [`CompactString::new()`]
+    ///
+    /// This method won't panic if the system is out-of-memory, but return an [`ReserveError`].
+    /// Otherwise it behaves the same as [`CompactString::new()`].
+    #[inline]
+    pub fn try_new<T: AsRef<str>>(text: T) -> Result<Self, ReserveError> {
+        Repr::new(text.as_ref()).map(CompactString)
+    }
+
     /// Creates a new inline [`CompactString`] at compile time.
     ///
     /// For most use cases you should use the method [`CompactString::from_static_str()`],
@@ -249,6 +266,9 @@ impl CompactString {
 
     /// Get back the `&'static str` constructed by [`CompactString::from_static_str`].
     ///
+    /// A [`CompactString`] never shares a buffer it owns, so this is the only case in which a
+    /// mutation first has to copy the string. If this returns `None`, mutations happen in place.
+    ///
     /// # Examples
     /// ```
     /// use compact_str::CompactString;
@@ -266,6 +286,32 @@ impl CompactString {
         self.0.as_static_str()
     }
 
+    /// Returns a string slice containing the entire [`CompactString`], like
+    /// [`CompactString::as_str()`], but usable in `const` contexts.
+    ///
+    /// Strings can only be heap allocated at runtime, so at compile time this is the contents of
+    /// an inlined string, or the `&'static str` a [`CompactString`] was created from. At runtime
+    /// this works for all strings, but prefer [`CompactString::as_str()`] there, which is
+    /// optimized to not branch on how the string is stored.
+    ///
+    /// Note: This is only a `const fn` on Rust 1.64 and newer.
+    ///
+    /// # Examples
+    /// ```
+    /// use compact_str::CompactString;
+    ///
+    /// const GREETING: CompactString = CompactString::new_inline("hello");
+    /// assert_eq!(GREETING.const_as_str(), "hello");
+    ///
+    /// let heap = CompactString::from("a string that is long enough to be heap allocated");
+    /// assert_eq!(heap.const_as_str(), heap.as_str());
+    /// ```
+    #[inline]
+    #[rustversion::attr(since(1.64), const)]
+    pub fn const_as_str(&self) -> &str {
+        self.0.const_as_str()
+    }
+
     /// Creates a new empty [`CompactString`] with the capacity to fit at least `capacity` bytes.
     ///
     /// A `CompactString` will inline strings on the stack, if they're small enough. Specifically,
@@ -335,6 +381,40 @@ impl CompactString {
         Repr::with_capacity(capacity).map(CompactString)
     }
 
+    /// Creates a new empty [`CompactString`] with a capacity of the sum of `lens`.
+    ///
+    /// This is useful when concatenating parts whose lengths are known up front, pushing each
+    /// of them afterwards with [`CompactString::push_str()`] then makes at most one allocation.
+    ///
+    /// # Panics
+    ///
+    /// This method panics if the sum of `lens` overflows, or if the system is out-of-memory.
+    ///
+    /// # Examples
+    /// ```
+    /// # use compact_str::CompactString;
+    /// let parts = ["a string ", "that is built ", "from a few parts"];
+    ///
+    /// let mut compact = CompactString::with_capacity_for(parts.iter().map(|part| part.len()));
+    /// let capacity = compact.capacity();
+    /// for part in &parts {
+    ///     compact.push_str(part);
+    /// }
+    ///
+    /// assert_eq!(compact, "a string that is built from a few parts");
+    /// assert_eq!(compact.capacity(), capacity);
+    /// ```
+    #[inline]
+    #[track_caller]
+    pub fn with_capacity_for<I: IntoIterator<Item = usize>>(lens: I) -> Self {
+        let capacity = lens
+            .into_iter()
+            .try_fold(0_usize, usize::checked_add)
+            .ok_or(ReserveError(()))
+            .unwrap_with_msg();
+        CompactString::with_capacity(capacity)
+    }
+
     /// Convert a slice of bytes into a [`CompactString`].
     ///
     /// A [`CompactString`] is a contiguous collection of bytes (`u8`s) that is valid [`UTF-8`](https://en.wikipedia.org/wiki/UTF-8).
@@ -367,6 +447,75 @@ impl CompactString {
         Repr::from_utf8(buf).map(CompactString)
     }
 
+    /// Creates a new [`CompactString`] from an iterator of bytes, e.g. a streaming decoder. The
+    /// bytes are collected into a buffer and validated as UTF-8 once, at the end.
+    ///
+    /// Note: Short strings are inlined, longer strings re-use the buffer the bytes were collected
+    /// into.
+    ///
+    /// # Examples
+    /// ### Valid UTF-8
+    /// ```
+    /// # use compact_str::CompactString;
+    /// let bytes = "hello 🦀".bytes();
+    /// let compact = CompactString::from_utf8_iter(bytes).expect("valid UTF-8");
+    ///
+    /// assert_eq!(compact, "hello 🦀");
+    /// ```
+    ///
+    /// ### Invalid UTF-8
+    /// ```
+    /// # use compact_str::CompactString;
+    /// let bytes = [104, 105, 255].iter().copied();
+    /// let result = CompactString::from_utf8_iter(bytes);
+    ///
+    /// assert!(result.is_err());
+    /// ```
+    pub fn from_utf8_iter<I: IntoIterator<Item = u8>>(iter: I) -> Result<Self, Utf8Error> {
+        let bytes: alloc::vec::Vec<u8> = iter.into_iter().collect();
+        match String::from_utf8(bytes) {
+            Ok(s) => Ok(CompactString::from(s)),
+            Err(e) => Err(e.utf8_error()),
+        }
+    }
+
+    /// Creates a new [`CompactString`] from an iterator of bytes with a known length, reserving
+    /// space for all of the bytes up front and validating them as UTF-8 once, at the end.
+    ///
+    /// Strings that are too long to be inlined re-use the buffer the bytes were collected into,
+    /// so only a single allocation is made, with a capacity of exactly `iter.len()`. Short strings
+    /// are inlined.
+    ///
+    /// # Examples
+    /// ### Valid UTF-8
+    /// ```
+    /// # use compact_str::CompactString;
+    /// let bytes = "a byte iterator that's long enough to be heap allocated 🦀".bytes();
+    /// let compact = CompactString::from_utf8_exact(bytes).expect("valid UTF-8");
+    ///
+    /// assert_eq!(compact, "a byte iterator that's long enough to be heap allocated 🦀");
+    /// assert_eq!(compact.capacity(), compact.len());
+    /// ```
+    ///
+    /// ### Invalid UTF-8
+    /// ```
+    /// # use compact_str::CompactString;
+    /// let bytes = [104, 105, 255].iter().copied();
+    /// let result = CompactString::from_utf8_exact(bytes);
+    ///
+    /// assert!(result.is_err());
+    /// ```
+    pub fn from_utf8_exact<I: ExactSizeIterator<Item = u8>>(iter: I) -> Result<Self, Utf8Error> {
+        // `len` is only a hint for how much to reserve, the iterator is still allowed to yield
+        // more or fewer bytes
+        let mut bytes = alloc::vec::Vec::with_capacity(iter.len());
+        bytes.extend(iter);
+        match String::from_utf8(bytes) {
+            Ok(s) => Ok(CompactString::from(s)),
+            Err(e) => Err(e.utf8_error()),
+        }
+    }
+
     /// Converts a vector of bytes to a [`CompactString`] without checking that the string contains
     /// valid UTF-8.
     ///
@@ -401,6 +550,64 @@ impl CompactString {
         CompactString(Repr::from_utf8_unchecked(buf).unwrap_with_msg())
     }
 
+    /// Converts an array of bytes to a [`CompactString`] without checking that it contains valid
+    /// UTF-8, e.g. for byte arrays emitted by code generation tools that are known to be valid.
+    ///
+    /// See the safe version, `CompactString::try_from([u8; N])`, which validates the bytes.
+    ///
+    /// # Safety
+    ///
+    /// The bytes passed in must be valid UTF-8, see [`CompactString::from_utf8_unchecked`].
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use compact_str::CompactString;
+    /// const SPARKLE_HEART: [u8; 4] = [240, 159, 146, 150];
+    ///
+    /// let sparkle_heart = unsafe { CompactString::from_utf8_array_unchecked(SPARKLE_HEART) };
+    ///
+    /// assert_eq!("💖", sparkle_heart);
+    /// ```
+    #[inline]
+    #[must_use]
+    #[track_caller]
+    pub unsafe fn from_utf8_array_unchecked<const N: usize>(buf: [u8; N]) -> Self {
+        CompactString::from_utf8_unchecked(buf)
+    }
+
+    /// Converts a vector of bytes to a [`CompactString`] without checking that it contains valid
+    /// UTF-8, re-using the vector's allocation, e.g. for bytes the caller produced themselves.
+    ///
+    /// Like [`CompactString::from_string_buffer`], the buffer is always re-used, even if the
+    /// string would fit inline, and no bytes are copied. To validate the bytes first, use
+    /// [`String::from_utf8`] and then [`CompactString::from_string_buffer`].
+    ///
+    /// # Safety
+    ///
+    /// The bytes passed in must be valid UTF-8. Passing invalid UTF-8 is undefined behavior, see
+    /// [`CompactString::from_utf8_unchecked`].
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use compact_str::CompactString;
+    /// let mut bytes = Vec::with_capacity(64);
+    /// bytes.extend_from_slice(b"hello world");
+    /// let ptr = bytes.as_ptr();
+    ///
+    /// let compact = unsafe { CompactString::from_utf8_unchecked_owned(bytes) };
+    ///
+    /// assert_eq!(compact, "hello world");
+    /// assert_eq!(compact.as_ptr(), ptr);
+    /// ```
+    #[inline]
+    #[must_use]
+    #[track_caller]
+    pub unsafe fn from_utf8_unchecked_owned(bytes: alloc::vec::Vec<u8>) -> Self {
+        CompactString::from_string_buffer(String::from_utf8_unchecked(bytes))
+    }
+
     /// Decode a [`UTF-16`](https://en.wikipedia.org/wiki/UTF-16) slice of bytes into a
     /// [`CompactString`], returning an [`Err`] if the slice contains any invalid data.
     ///
@@ -441,6 +648,29 @@ impl CompactString {
         Ok(ret)
     }
 
+    /// Decode a [`UTF-16`](https://en.wikipedia.org/wiki/UTF-16) slice into a [`CompactString`],
+    /// stripping a leading byte order mark (`U+FEFF`) if there is one.
+    ///
+    /// Text read from Windows APIs or files often starts with a byte order mark, which
+    /// [`CompactString::from_utf16()`] would otherwise keep as the first [`char`].
+    ///
+    /// # Examples
+    /// ```
+    /// # use compact_str::CompactString;
+    /// let buf: &[u16] = &[0xFEFF, 0x0068, 0x0069];
+    ///
+    /// assert_eq!(CompactString::from_utf16_with_bom(buf).unwrap(), "hi");
+    /// assert_eq!(CompactString::from_utf16(buf).unwrap(), "\u{FEFF}hi");
+    /// ```
+    #[inline]
+    pub fn from_utf16_with_bom<B: AsRef<[u16]>>(buf: B) -> Result<Self, Utf16Error> {
+        let buf = match buf.as_ref() {
+            [0xFEFF, rest @ ..] => rest,
+            buf => buf,
+        };
+        CompactString::from_utf16(buf)
+    }
+
     /// Decode a UTF-16–encoded slice `v` into a `CompactString`, replacing invalid data with
     /// the replacement character (`U+FFFD`), �.
     ///
@@ -491,6 +721,26 @@ impl CompactString {
         self.0.len()
     }
 
+    /// Returns the length of the [`CompactString`] in `UTF-16` code units, e.g. what JavaScript's
+    /// `String.length` reports for the same string.
+    ///
+    /// [`char`]s in the Basic Multilingual Plane are one code unit long, all others, e.g. most
+    /// emoji, are two code units long.
+    ///
+    /// # Examples
+    /// ```
+    /// # use compact_str::CompactString;
+    /// let ascii = CompactString::new("hello world");
+    /// assert_eq!(ascii.len_utf16(), 11);
+    ///
+    /// let emoji = CompactString::new("👱");
+    /// assert_eq!(emoji.len_utf16(), 2);
+    /// ```
+    #[inline]
+    pub fn len_utf16(&self) -> usize {
+        self.chars().map(char::len_utf16).sum()
+    }
+
     /// Returns `true` if the [`CompactString`] has a length of 0, `false` otherwise
     ///
     /// # Examples
@@ -512,6 +762,8 @@ impl CompactString {
     ///
     /// # Note
     /// * A `CompactString` will always have a capacity of at least `std::mem::size_of::<String>()`
+    /// * A `CompactString` that is stored inline always has a capacity of exactly
+    ///   `std::mem::size_of::<String>()`, and pushing up to that many bytes won't heap allocate
     ///
     /// # Examples
     /// ### Minimum Size
@@ -534,6 +786,47 @@ impl CompactString {
         self.0.capacity()
     }
 
+    /// Returns the capacity a [`CompactString`] created from a string of `len` bytes would have.
+    ///
+    /// Strings that fit inline always report the inline capacity, longer strings allocate at least
+    /// `len` bytes on the heap, with a small minimum allocation size.
+    ///
+    /// # Examples
+    /// ```
+    /// # use compact_str::CompactString;
+    /// let min_size = std::mem::size_of::<String>();
+    /// assert_eq!(CompactString::capacity_for(0), min_size);
+    /// assert_eq!(CompactString::capacity_for(5), min_size);
+    ///
+    /// let long = "a".repeat(100);
+    /// assert_eq!(CompactString::capacity_for(100), 100);
+    /// assert_eq!(CompactString::new(&long).capacity(), CompactString::capacity_for(100));
+    /// ```
+    #[inline]
+    pub const fn capacity_for(len: usize) -> usize {
+        Repr::capacity_for(len)
+    }
+
+    /// Returns whether a string of `len` bytes can be stored inline, without allocating.
+    ///
+    /// This is a `const fn`, so it can be used in constant expressions, e.g. to assert at compile
+    /// time that some known string will never allocate.
+    ///
+    /// # Examples
+    /// ```
+    /// # use compact_str::CompactString;
+    /// const ID: &str = "user_id";
+    /// const _: () = assert!(CompactString::fits_inline(ID.len()));
+    ///
+    /// let max = std::mem::size_of::<String>();
+    /// assert!(CompactString::fits_inline(max));
+    /// assert!(!CompactString::fits_inline(max + 1));
+    /// ```
+    #[inline]
+    pub const fn fits_inline(len: usize) -> bool {
+        len <= repr::MAX_SIZE
+    }
+
     /// Ensures that this [`CompactString`]'s capacity is at least `additional` bytes longer than
     /// its length. The capacity may be increased by more than `additional` bytes if it chooses,
     /// to prevent frequent reallocations.
@@ -573,6 +866,74 @@ impl CompactString {
         self.0.reserve(additional)
     }
 
+    /// Ensures that this [`CompactString`]'s capacity is at least `additional` bytes longer than
+    /// its length. Unlike [`CompactString::reserve()`], this will not deliberately over-allocate
+    /// to avoid frequent reallocations.
+    ///
+    /// Prefer [`CompactString::reserve()`] if many insertions are expected.
+    ///
+    /// # Note
+    /// * A `CompactString` will always have at least a capacity of `std::mem::size_of::<String>()`
+    /// * Heap allocations have a small minimum size, so the capacity might still be larger than
+    ///   requested
+    ///
+    /// # Panics
+    /// This method panics if the new capacity overflows `usize` or if the system is out-of-memory.
+    /// Use [`CompactString::try_reserve_exact()`] if you want to handle such a problem manually.
+    ///
+    /// # Examples
+    /// ```
+    /// # use compact_str::CompactString;
+    /// let mut compact = CompactString::new("a string that is too long to be inlined");
+    ///
+    /// compact.reserve_exact(100);
+    /// assert_eq!(compact.capacity(), compact.len() + 100);
+    /// ```
+    #[inline]
+    #[track_caller]
+    pub fn reserve_exact(&mut self, additional: usize) {
+        self.0.reserve_exact(additional).unwrap_with_msg()
+    }
+
+    /// Fallible version of [`CompactString::reserve_exact()`]
+    ///
+    /// This method won't panic if the system is out-of-memory, but return an [`ReserveError`]
+    /// Otherwise it behaves the same as [`CompactString::reserve_exact()`].
+    #[inline]
+    pub fn try_reserve_exact(&mut self, additional: usize) -> Result<(), ReserveError> {
+        self.0.reserve_exact(additional)
+    }
+
+    /// Same as [`CompactString::reserve()`], but returns whether or not memory had to be
+    /// allocated, or reallocated, to fit the `additional` bytes.
+    ///
+    /// Returns `false` if the request was satisfied in place, including when a `&'static str`
+    /// gets copied inline.
+    ///
+    /// # Panics
+    /// This method panics if the new capacity overflows `usize` or if the system is out-of-memory.
+    ///
+    /// # Examples
+    /// ```
+    /// # use compact_str::CompactString;
+    /// let mut compact = CompactString::with_capacity(100);
+    ///
+    /// // there's already enough room
+    /// assert!(!compact.reserve_reporting(50));
+    /// // but now we need to grow
+    /// assert!(compact.reserve_reporting(200));
+    /// ```
+    #[inline]
+    #[track_caller]
+    pub fn reserve_reporting(&mut self, additional: usize) -> bool {
+        let was_heap_allocated = self.is_heap_allocated();
+        let capacity = self.capacity();
+
+        self.reserve(additional);
+
+        self.is_heap_allocated() && (!was_heap_allocated || self.capacity() != capacity)
+    }
+
     /// Returns a string slice containing the entire [`CompactString`].
     ///
     /// # Examples
@@ -589,6 +950,10 @@ impl CompactString {
 
     /// Returns a mutable string slice containing the entire [`CompactString`].
     ///
+    /// The slice only covers the `len()` bytes of the string, not any spare capacity. If the
+    /// [`CompactString`] was created from a `&'static str`, the string first gets copied into a
+    /// buffer we own, similar to `Arc::make_mut`. Otherwise the string is mutated in place.
+    ///
     /// # Examples
     /// ```
     /// # use compact_str::CompactString;
@@ -603,66 +968,320 @@ impl CompactString {
         unsafe { core::str::from_utf8_unchecked_mut(&mut self.0.as_mut_buf()[..len]) }
     }
 
-    unsafe fn spare_capacity_mut(&mut self) -> &mut [mem::MaybeUninit<u8>] {
-        let buf = self.0.as_mut_buf();
-        let ptr = buf.as_mut_ptr();
-        let cap = buf.len();
-        let len = self.len();
+    /// Calls `f` with the contents of the [`CompactString`] as a `Vec<u8>`, and then stores the
+    /// possibly modified bytes back into the [`CompactString`].
+    ///
+    /// This gives access to all of the methods of [`Vec`], while making sure the
+    /// [`CompactString`] still contains valid UTF-8 afterwards. Heap allocated strings are moved
+    /// into the `Vec` and back without copying.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `f` leaves the bytes as invalid UTF-8. If this happens, or if `f` panics, the
+    /// [`CompactString`] is left empty.
+    ///
+    /// # Examples
+    /// ```
+    /// # use compact_str::CompactString;
+    /// let mut s = CompactString::new("hello world");
+    ///
+    /// let removed = s.with_mut_bytes(|bytes| bytes.drain(5..).count());
+    /// assert_eq!(removed, 6);
+    /// assert_eq!(s, "hello");
+    /// ```
+    #[track_caller]
+    pub fn with_mut_bytes<R>(&mut self, f: impl FnOnce(&mut alloc::vec::Vec<u8>) -> R) -> R {
+        let mut bytes = alloc::vec::Vec::from(mem::take(self));
+        let result = f(&mut bytes);
 
-        slice::from_raw_parts_mut(ptr.add(len) as *mut mem::MaybeUninit<u8>, cap - len)
+        match String::from_utf8(bytes) {
+            Ok(s) => *self = CompactString::from(s),
+            Err(err) => panic!("with_mut_bytes left invalid UTF-8: {}", err.utf8_error()),
+        }
+        result
     }
 
-    /// Returns a byte slice of the [`CompactString`]'s contents.
+    /// Calls `f` with the bytes of the [`CompactString`], so they can be transformed in place,
+    /// and then checks that they're still valid UTF-8.
+    ///
+    /// Unlike [`CompactString::as_mut_bytes()`] this is safe, but the length of the string can't
+    /// change, use [`CompactString::with_mut_bytes()`] for that. The slice only covers the
+    /// `len()` bytes of the string, not any spare capacity. If the [`CompactString`] was created
+    /// from a `&'static str`, the string first gets copied into a buffer we own.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `f` leaves the bytes as invalid UTF-8. If this happens, or if `f` panics, the
+    /// [`CompactString`] is left empty.
     ///
     /// # Examples
     /// ```
     /// # use compact_str::CompactString;
-    /// let s = CompactString::new("hello");
+    /// let mut s = CompactString::new("hello world");
     ///
-    /// assert_eq!(&[104, 101, 108, 108, 111], s.as_bytes());
+    /// s.bytes_in_place(|bytes| bytes.reverse());
+    /// assert_eq!(s, "dlrow olleh");
     /// ```
-    #[inline]
-    pub fn as_bytes(&self) -> &[u8] {
-        &self.0.as_slice()[..self.len()]
+    #[track_caller]
+    pub fn bytes_in_place<F: FnOnce(&mut [u8])>(&mut self, f: F) {
+        // take the string, so `self` is left empty if `f` panics or leaves invalid UTF-8
+        let mut compact = mem::take(self);
+        let len = compact.len();
+
+        // SAFETY: We check the bytes are valid UTF-8 before putting the string back into `self`
+        let bytes = unsafe { &mut compact.as_mut_bytes()[..len] };
+        f(bytes);
+
+        if let Err(err) = core::str::from_utf8(bytes) {
+            panic!("bytes_in_place left invalid UTF-8: {}", err);
+        }
+        *self = compact;
     }
 
-    // TODO: Implement a `try_as_mut_slice(...)` that will fail if it results in cloning?
-    //
-    /// Provides a mutable reference to the underlying buffer of bytes.
+    /// Returns the remaining spare capacity of the [`CompactString`] as a slice of
+    /// `MaybeUninit<u8>`.
+    ///
+    /// The returned slice can be used to fill the [`CompactString`] with data (e.g. by reading
+    /// from a decoder) before marking the data as initialized using
+    /// [`CompactString::set_len()`]. Use [`CompactString::reserve_exact()`] beforehand to make
+    /// sure there's enough room. This mirrors [`Vec::spare_capacity_mut()`].
+    ///
+    /// If the [`CompactString`] was created from a `&'static str`, its contents are first copied
+    /// into a buffer we own, which might allocate.
     ///
     /// # Safety
-    /// * All Rust strings, including `CompactString`, must be valid UTF-8. The caller must
-    ///   guarantee
-    /// that any modifications made to the underlying buffer are valid UTF-8.
+    /// * For inlined strings the spare capacity overlaps with the byte that stores the length, so
+    ///   after writing to the returned slice [`CompactString::set_len()`] must be called before
+    ///   the [`CompactString`] is used or dropped
+    /// * As with [`CompactString::set_len()`], the bytes up to the new length must be valid UTF-8
     ///
     /// # Examples
     /// ```
     /// # use compact_str::CompactString;
-    /// let mut s = CompactString::new("hello");
+    /// let mut compact = CompactString::new("abc");
+    /// compact.reserve_exact(3);
     ///
-    /// let slice = unsafe { s.as_mut_bytes() };
-    /// // copy bytes into our string
-    /// slice[5..11].copy_from_slice(" world".as_bytes());
-    /// // set the len of the string
-    /// unsafe { s.set_len(11) };
+    /// unsafe {
+    ///     let spare = compact.spare_capacity_mut();
+    ///     spare[0].write(b'd');
+    ///     spare[1].write(b'e');
+    ///     spare[2].write(b'f');
     ///
-    /// assert_eq!(s, "hello world");
+    ///     compact.set_len(6);
+    /// }
+    /// assert_eq!(compact, "abcdef");
     /// ```
     #[inline]
-    pub unsafe fn as_mut_bytes(&mut self) -> &mut [u8] {
-        self.0.as_mut_buf()
+    pub unsafe fn spare_capacity_mut(&mut self) -> &mut [mem::MaybeUninit<u8>] {
+        let buf = self.0.as_mut_buf();
+        let ptr = buf.as_mut_ptr();
+        let cap = buf.len();
+        let len = self.len();
+
+        slice::from_raw_parts_mut(ptr.add(len) as *mut mem::MaybeUninit<u8>, cap - len)
     }
 
-    /// Appends the given [`char`] to the end of this [`CompactString`].
+    /// Reserves room for at least `additional` more bytes, and returns the spare capacity of the
+    /// [`CompactString`] as a slice of `MaybeUninit<u8>` that's at least `additional` bytes long.
+    ///
+    /// This combines [`CompactString::reserve()`] and [`CompactString::spare_capacity_mut()`]
+    /// for the common pattern of a decoder loop, which writes directly into the returned slice
+    /// and then marks the bytes as initialized using [`CompactString::set_len()`].
+    ///
+    /// # Panics
+    ///
+    /// This method panics if the new capacity overflows `usize`, or if the system is
+    /// out-of-memory.
+    ///
+    /// # Safety
+    /// The same requirements as for [`CompactString::spare_capacity_mut()`] apply.
     ///
     /// # Examples
     /// ```
     /// # use compact_str::CompactString;
-    /// let mut s = CompactString::new("foo");
+    /// let mut compact = CompactString::new("abc");
     ///
-    /// s.push('b');
-    /// s.push('a');
-    /// s.push('r');
+    /// unsafe {
+    ///     let spare = compact.reserve_and_spare(3);
+    ///     assert!(spare.len() >= 3);
+    ///     spare[0].write(b'd');
+    ///     spare[1].write(b'e');
+    ///     spare[2].write(b'f');
+    ///
+    ///     compact.set_len(6);
+    /// }
+    /// assert_eq!(compact, "abcdef");
+    /// ```
+    #[inline]
+    #[track_caller]
+    pub unsafe fn reserve_and_spare(&mut self, additional: usize) -> &mut [mem::MaybeUninit<u8>] {
+        self.reserve(additional);
+        self.spare_capacity_mut()
+    }
+
+    /// Returns a byte slice of the [`CompactString`]'s contents.
+    ///
+    /// # Examples
+    /// ```
+    /// # use compact_str::CompactString;
+    /// let s = CompactString::new("hello");
+    ///
+    /// assert_eq!(&[104, 101, 108, 108, 111], s.as_bytes());
+    /// ```
+    #[inline]
+    pub fn as_bytes(&self) -> &[u8] {
+        &self.0.as_slice()[..self.len()]
+    }
+
+    /// Returns whether the bytes of the [`CompactString`] are equal to `other`, e.g. to compare
+    /// raw bytes read by protocol code against a known string.
+    ///
+    /// `other` doesn't need to be valid UTF-8, bytes that aren't valid UTF-8 are never equal to a
+    /// [`CompactString`]. This is the same as comparing with `==`, which also works with byte
+    /// slices.
+    ///
+    /// # Examples
+    /// ```
+    /// # use compact_str::CompactString;
+    /// let method = CompactString::new("GET");
+    ///
+    /// assert!(method.eq_bytes(b"GET"));
+    /// assert!(!method.eq_bytes(b"POST"));
+    /// assert!(!method.eq_bytes(b"GE\xFF"));
+    /// ```
+    #[inline]
+    pub fn eq_bytes(&self, other: &[u8]) -> bool {
+        self.as_bytes() == other
+    }
+
+    /// Returns the byte at index `idx`, or `None` if `idx` is out of bounds.
+    ///
+    /// Like [`str`], [`CompactString`] doesn't implement `Index<usize>`, because a single byte
+    /// isn't necessarily a whole [`char`]. Use [`CompactString::char_at()`] or a range to get at
+    /// the characters instead.
+    ///
+    /// # Examples
+    /// ```
+    /// # use compact_str::CompactString;
+    /// let s = CompactString::new("hé");
+    ///
+    /// assert_eq!(s.byte(0), Some(b'h'));
+    /// // the first byte of 'é'
+    /// assert_eq!(s.byte(1), Some(0xC3));
+    /// assert_eq!(s.byte(3), None);
+    /// ```
+    #[inline]
+    pub fn byte(&self, idx: usize) -> Option<u8> {
+        self.as_bytes().get(idx).copied()
+    }
+
+    /// Copies the bytes of the [`CompactString`] into a fixed size array, returning the array and
+    /// the number of bytes that were written, or `None` if the string is longer than `N` bytes.
+    ///
+    /// The remaining bytes in the array are set to `0`.
+    ///
+    /// # Examples
+    /// ```
+    /// # use compact_str::CompactString;
+    /// let s = CompactString::new("hello");
+    ///
+    /// let (buf, len) = s.copy_to_array::<8>().unwrap();
+    /// assert_eq!(&buf[..len], b"hello");
+    /// assert_eq!(buf, *b"hello\0\0\0");
+    ///
+    /// // too long to fit
+    /// assert_eq!(s.copy_to_array::<4>(), None);
+    /// ```
+    #[inline]
+    pub fn copy_to_array<const N: usize>(&self) -> Option<([u8; N], usize)> {
+        let bytes = self.as_bytes();
+        let mut array = [0; N];
+        array.get_mut(..bytes.len())?.copy_from_slice(bytes);
+
+        Some((array, bytes.len()))
+    }
+
+    // TODO: Implement a `try_as_mut_slice(...)` that will fail if it results in cloning?
+    //
+    /// Provides a mutable reference to the underlying buffer of bytes.
+    ///
+    /// # Safety
+    /// * All Rust strings, including `CompactString`, must be valid UTF-8. The caller must
+    ///   guarantee
+    /// that any modifications made to the underlying buffer are valid UTF-8.
+    ///
+    /// # Examples
+    /// ```
+    /// # use compact_str::CompactString;
+    /// let mut s = CompactString::new("hello");
+    ///
+    /// let slice = unsafe { s.as_mut_bytes() };
+    /// // copy bytes into our string
+    /// slice[5..11].copy_from_slice(" world".as_bytes());
+    /// // set the len of the string
+    /// unsafe { s.set_len(11) };
+    ///
+    /// assert_eq!(s, "hello world");
+    /// ```
+    #[inline]
+    pub unsafe fn as_mut_bytes(&mut self) -> &mut [u8] {
+        self.0.as_mut_buf()
+    }
+
+    /// Returns the [`char`] whose encoding starts at byte index `byte_idx`.
+    ///
+    /// Returns `None` if `byte_idx` is out of bounds, or does not lie on a [`char`] boundary.
+    ///
+    /// # Examples
+    /// ```
+    /// # use compact_str::CompactString;
+    /// let s = CompactString::new("a🦀b");
+    ///
+    /// assert_eq!(s.char_at(0), Some('a'));
+    /// assert_eq!(s.char_at(1), Some('🦀'));
+    /// assert_eq!(s.char_at(5), Some('b'));
+    ///
+    /// // inside of '🦀'
+    /// assert_eq!(s.char_at(2), None);
+    /// // out of bounds
+    /// assert_eq!(s.char_at(6), None);
+    /// ```
+    #[inline]
+    pub fn char_at(&self, byte_idx: usize) -> Option<char> {
+        self.as_str().get(byte_idx..)?.chars().next()
+    }
+
+    /// Returns an iterator over every byte index that lies on a [`char`] boundary, i.e. the
+    /// start of every [`char`], followed by the length of the string.
+    ///
+    /// These are exactly the indices for which [`str::is_char_boundary()`] returns `true`, which
+    /// is useful to e.g. snap a cursor position to the closest valid index.
+    ///
+    /// # Examples
+    /// ```
+    /// # use compact_str::CompactString;
+    /// let s = CompactString::new("a🦀b");
+    /// let boundaries: Vec<usize> = s.char_boundaries().collect();
+    ///
+    /// assert_eq!(boundaries, [0, 1, 5, 6]);
+    /// ```
+    #[inline]
+    pub fn char_boundaries(&self) -> impl Iterator<Item = usize> + '_ {
+        self.char_indices()
+            .map(|(idx, _)| idx)
+            .chain(core::iter::once(self.len()))
+    }
+
+    /// Appends the given [`char`] to the end of this [`CompactString`].
+    ///
+    /// # Examples
+    /// ```
+    /// # use compact_str::CompactString;
+    /// let mut s = CompactString::new("foo");
+    ///
+    /// s.push('b');
+    /// s.push('a');
+    /// s.push('r');
     ///
     /// assert_eq!("foobar", s);
     /// ```
@@ -689,6 +1308,38 @@ impl CompactString {
         self.0.pop()
     }
 
+    /// Removes the last character from the [`CompactString`] and returns it, releasing excess
+    /// capacity as the string gets shorter.
+    ///
+    /// [`CompactString::pop()`] never changes the capacity of the string. This method instead
+    /// halves the capacity of a heap allocated string once its length drops to a quarter of the
+    /// capacity, which keeps popping in a loop amortized *O*(1) while memory usage shrinks along
+    /// with the string. Once the string is short enough, it gets inlined.
+    ///
+    /// Returns `None` if this [`CompactString`] is empty.
+    ///
+    /// # Examples
+    /// ```
+    /// # use compact_str::CompactString;
+    /// let mut s = CompactString::with_capacity(1024);
+    /// s.push_str("abc");
+    /// assert_eq!(s.capacity(), 1024);
+    ///
+    /// assert_eq!(s.pop_and_shrink(), Some('c'));
+    /// assert!(s.capacity() < 1024);
+    /// ```
+    #[inline]
+    pub fn pop_and_shrink(&mut self) -> Option<char> {
+        let ch = self.pop()?;
+
+        let capacity = self.capacity();
+        if self.is_heap_allocated() && self.len() <= capacity / 4 {
+            self.shrink_to(capacity / 2);
+        }
+
+        Some(ch)
+    }
+
     /// Appends a given string slice onto the end of this [`CompactString`]
     ///
     /// # Examples
@@ -705,6 +1356,51 @@ impl CompactString {
         self.0.push_str(s)
     }
 
+    /// Concatenates two [`CompactString`]s, reusing whichever buffer already has room for the
+    /// result.
+    ///
+    /// If `self` has enough spare capacity, `other` is appended in place. Otherwise, if `other` is
+    /// heap allocated and has enough spare capacity, `self` is inserted at its front. If neither
+    /// has room, a single buffer sized for the combined length is allocated.
+    ///
+    /// A [`CompactString`] created from a `&'static str` has no spare capacity, so it's always
+    /// copied and never modified.
+    ///
+    /// # Panics
+    /// This method panics if the new capacity overflows `usize` or if the system is out-of-memory.
+    ///
+    /// # Examples
+    /// ```
+    /// # use compact_str::CompactString;
+    /// let mut hello = CompactString::with_capacity(64);
+    /// hello.push_str("hello ");
+    /// let ptr = hello.as_ptr();
+    ///
+    /// let hello_world = hello.concat(CompactString::new("world"));
+    /// assert_eq!(hello_world, "hello world");
+    ///
+    /// // the buffer of `hello` was reused
+    /// assert_eq!(hello_world.as_ptr(), ptr);
+    /// ```
+    #[must_use]
+    #[track_caller]
+    pub fn concat(mut self, mut other: CompactString) -> Self {
+        let len = self.len();
+        let other_len = other.len();
+
+        if self.capacity() - len >= other_len {
+            self.push_str(&other);
+            self
+        } else if other.is_heap_allocated() && other.capacity() - other_len >= len {
+            other.insert_str(0, &self);
+            other
+        } else {
+            self.reserve_exact(other_len);
+            self.push_str(&other);
+            self
+        }
+    }
+
     /// Removes a [`char`] from this [`CompactString`] at a byte position and returns it.
     ///
     /// This is an *O*(*n*) operation, as it requires copying every element in the
@@ -771,6 +1467,71 @@ impl CompactString {
         ch
     }
 
+    /// Removes a [`char`] from this [`CompactString`] at a byte position and returns it, replacing
+    /// it with the last [`char`] of the string.
+    ///
+    /// This does not preserve ordering, but unlike [`CompactString::remove()`] it doesn't need to
+    /// shift the entire tail of the string. Only when the removed and the last [`char`] have a
+    /// different UTF-8 length, the bytes in between are shifted by the difference.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `idx` is larger than or equal to the [`CompactString`]'s length,
+    /// or if it does not lie on a [`char`] boundary.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use compact_str::CompactString;
+    /// let mut c = CompactString::from("abcde");
+    ///
+    /// assert_eq!(c.swap_remove(1), 'b');
+    /// assert_eq!(c, "aecd");
+    ///
+    /// assert_eq!(c.swap_remove(3), 'd');
+    /// assert_eq!(c, "aec");
+    /// ```
+    pub fn swap_remove(&mut self, idx: usize) -> char {
+        let len = self.len();
+        let ch = self.as_str()[idx..]
+            .chars()
+            .next()
+            .expect("cannot remove a char from the end of a string");
+        let ch_len = ch.len_utf8();
+
+        // removing the last char doesn't require moving anything
+        if idx + ch_len == len {
+            // SAFETY: `idx` is a char boundary, since we just sliced there
+            unsafe { self.set_len(idx) };
+            return ch;
+        }
+
+        let mut last_buf = [0; 4];
+        let last = self.as_str().chars().next_back().expect("string is not empty");
+        let last = last.encode_utf8(&mut last_buf).as_bytes();
+
+        // the bytes between the removed and the last char
+        let middle_start = idx + ch_len;
+        let middle_len = len - last.len() - middle_start;
+
+        // SAFETY: `idx`, `middle_start`, and `len - last.len()` are all char boundaries, and all
+        // of the ranges are within our length. We fill the gap with the bytes of a valid `char`.
+        unsafe {
+            let ptr = self.as_mut_ptr();
+
+            // make exactly enough room for the last char, if it has a different length
+            if ch_len != last.len() {
+                core::ptr::copy(ptr.add(middle_start), ptr.add(idx + last.len()), middle_len);
+            }
+            // then write the last char into the gap
+            core::ptr::copy_nonoverlapping(last.as_ptr(), ptr.add(idx), last.len());
+
+            self.set_len(len - ch_len);
+        }
+
+        ch
+    }
+
     /// Forces the length of the [`CompactString`] to `new_len`.
     ///
     /// This is a low-level operation that maintains none of the normal invariants for
@@ -780,6 +1541,7 @@ impl CompactString {
     /// # Safety
     /// * `new_len` must be less than or equal to `capacity()`
     /// * The elements at `old_len..new_len` must be initialized
+    /// * The elements at `0..new_len` must be valid UTF-8
     #[inline]
     pub unsafe fn set_len(&mut self, new_len: usize) {
         self.0.set_len(new_len)
@@ -808,6 +1570,56 @@ impl CompactString {
         self.0.is_heap_allocated()
     }
 
+    /// Returns the number of bytes this [`CompactString`] currently owns on the heap, e.g. so
+    /// memory profilers can attribute usage.
+    ///
+    /// This is `0` for inlined strings and strings created from a `&'static str`. For heap
+    /// allocated strings it's the full size of the allocation, which is at least
+    /// [`CompactString::capacity()`] bytes, and also includes the capacity itself if that's
+    /// stored on the heap, along with any padding. A [`CompactString`] never shares its buffer, so
+    /// this is always the entire allocation.
+    ///
+    /// # Examples
+    /// ```
+    /// # use compact_str::CompactString;
+    /// let hello = CompactString::new("hello world");
+    /// assert_eq!(hello.heap_size(), 0);
+    ///
+    /// let msg = CompactString::new("this message will self destruct in 5, 4, 3, 2, 1 💥");
+    /// assert!(msg.heap_size() >= msg.capacity());
+    /// ```
+    #[inline]
+    pub fn heap_size(&self) -> usize {
+        self.0.heap_size()
+    }
+
+    /// Asserts that the [`CompactString`] is not heap allocated, so performance sensitive code
+    /// can guard against values unexpectedly growing beyond the inline limit.
+    ///
+    /// Like [`debug_assert!`], the check only happens when debug assertions are enabled, so it
+    /// has no cost in release builds. Strings created from a `&'static str` don't heap allocate,
+    /// so they pass this check. See also the [`debug_assert_inline!`] macro.
+    ///
+    /// # Panics
+    ///
+    /// Panics if debug assertions are enabled and the [`CompactString`] is heap allocated.
+    ///
+    /// # Examples
+    /// ```
+    /// # use compact_str::CompactString;
+    /// let hello = CompactString::new("hello world");
+    /// hello.assert_inline();
+    /// ```
+    #[inline]
+    #[track_caller]
+    pub fn assert_inline(&self) {
+        debug_assert!(
+            !self.is_heap_allocated(),
+            "expected CompactString to be inline, but it's heap allocated with length {}",
+            self.len(),
+        );
+    }
+
     /// Ensure that the given range is inside the set data, and that no codepoints are split.
     ///
     /// Returns the range `start..end` as a tuple.
@@ -961,6 +1773,18 @@ impl CompactString {
             Self::new_inline("")
         } else if n == 1 {
             self.clone()
+        } else if self.len() == 1 {
+            // Repeating a single byte can be done with a `memset`, instead of copying the string
+            // `n` times
+            let byte = self.as_bytes()[0];
+            let mut out = Self::with_capacity(n);
+            // SAFETY: A single byte of valid UTF-8 is an ASCII character, so repeating it results
+            // in valid UTF-8. `out` has a capacity of at least `n` bytes.
+            unsafe {
+                out.as_mut_bytes()[..n].fill(byte);
+                out.set_len(n);
+            }
+            out
         } else {
             let mut out = Self::with_capacity(self.len() * n);
             (0..n).for_each(|_| out.push_str(self));
@@ -968,6 +1792,163 @@ impl CompactString {
         }
     }
 
+    /// Creates a new [`CompactString`] by repeating a string `n` times, with `sep` in between
+    /// each repetition.
+    ///
+    /// The length of the result is computed up front, so at most one allocation is made.
+    ///
+    /// # Panics
+    ///
+    /// This function will panic if the capacity would overflow.
+    ///
+    /// # Examples
+    ///
+    /// Basic usage:
+    ///
+    /// ```
+    /// use compact_str::CompactString;
+    /// let placeholders = CompactString::new("?").repeat_with_sep(3, ",");
+    /// assert_eq!(placeholders, "?,?,?");
+    /// ```
+    ///
+    /// A panic upon overflow:
+    ///
+    /// ```should_panic
+    /// use compact_str::CompactString;
+    ///
+    /// // this will panic at runtime
+    /// let huge = CompactString::new("?").repeat_with_sep(usize::MAX, ",");
+    /// ```
+    #[must_use]
+    #[track_caller]
+    pub fn repeat_with_sep(&self, n: usize, sep: &str) -> Self {
+        if n == 0 {
+            return Self::new_inline("");
+        }
+
+        let len = sep
+            .len()
+            .checked_mul(n - 1)
+            .and_then(|seps_len| self.len().checked_mul(n)?.checked_add(seps_len))
+            .ok_or(ReserveError(()))
+            .unwrap_with_msg();
+        if len == 0 {
+            return Self::new_inline("");
+        }
+
+        let mut out = Self::with_capacity(len);
+        out.push_str(self);
+        for _ in 1..n {
+            out.push_str(sep);
+            out.push_str(self);
+        }
+        out
+    }
+
+    /// Replaces the first `count` matches of `from` with `to`, returning a new [`CompactString`].
+    ///
+    /// This mirrors [`str::replacen`], but the result is a [`CompactString`], so short results
+    /// are stored inline. If `count` is larger than the number of matches, all of them get
+    /// replaced, if it's `0` the string is copied unchanged.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use compact_str::CompactString;
+    /// let s = CompactString::new("foo foo 123 foo");
+    ///
+    /// assert_eq!(s.replacen("foo", "new", 2), "new new 123 foo");
+    /// assert_eq!(s.replacen("foo", "new", 10), "new new 123 new");
+    /// assert_eq!(s.replacen("foo", "new", 0), "foo foo 123 foo");
+    /// ```
+    #[must_use]
+    pub fn replacen(&self, from: &str, to: &str, count: usize) -> Self {
+        // Most replacements don't change the length by much, so we start with the current length
+        let mut result = Self::with_capacity(self.len());
+        let mut last_end = 0;
+        for (start, part) in self.match_indices(from).take(count) {
+            result.push_str(&self[last_end..start]);
+            result.push_str(to);
+            last_end = start + part.len();
+        }
+        result.push_str(&self[last_end..]);
+        result
+    }
+
+    /// Returns a copy of the [`CompactString`] padded at the start with `fill`, so it's at least
+    /// `width` characters long.
+    ///
+    /// The width is measured in [`char`]s, not bytes. Strings that are already at least `width`
+    /// characters long are copied unchanged. This is useful for aligning output when the width
+    /// is only known at runtime.
+    ///
+    /// # Panics
+    ///
+    /// This function will panic if the capacity would overflow.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use compact_str::CompactString;
+    /// let s = CompactString::new("42");
+    ///
+    /// assert_eq!(s.pad_start(5, '0'), "00042");
+    /// assert_eq!(s.pad_start(1, '0'), "42");
+    /// ```
+    #[must_use]
+    #[track_caller]
+    pub fn pad_start(&self, width: usize, fill: char) -> Self {
+        let (mut out, padding) = self.padding_buffer(width, fill);
+        for _ in 0..padding {
+            out.push(fill);
+        }
+        out.push_str(self);
+        out
+    }
+
+    /// Returns a copy of the [`CompactString`] padded at the end with `fill`, so it's at least
+    /// `width` characters long.
+    ///
+    /// See [`CompactString::pad_start()`] for details.
+    ///
+    /// # Panics
+    ///
+    /// This function will panic if the capacity would overflow.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use compact_str::CompactString;
+    /// let s = CompactString::new("név");
+    ///
+    /// assert_eq!(s.pad_end(5, '.'), "név..");
+    /// assert_eq!(s.pad_end(3, '.'), "név");
+    /// ```
+    #[must_use]
+    #[track_caller]
+    pub fn pad_end(&self, width: usize, fill: char) -> Self {
+        let (mut out, padding) = self.padding_buffer(width, fill);
+        out.push_str(self);
+        for _ in 0..padding {
+            out.push(fill);
+        }
+        out
+    }
+
+    /// Returns an empty buffer large enough to hold `self` padded to `width` with `fill`, and
+    /// the number of `fill` chars needed
+    #[track_caller]
+    fn padding_buffer(&self, width: usize, fill: char) -> (Self, usize) {
+        let padding = width.saturating_sub(self.chars().count());
+        let len = fill
+            .len_utf8()
+            .checked_mul(padding)
+            .and_then(|padding_len| padding_len.checked_add(self.len()))
+            .ok_or(ReserveError(()))
+            .unwrap_with_msg();
+        (Self::with_capacity(len), padding)
+    }
+
     /// Truncate the [`CompactString`] to a shorter length.
     ///
     /// If the length of the [`CompactString`] is less or equal to `new_len`, the call is a no-op.
@@ -994,13 +1975,34 @@ impl CompactString {
             return;
         }
 
-        assert!(
-            s.is_char_boundary(new_len),
-            "new_len must lie on char boundary",
-        );
+        if !s.is_char_boundary(new_len) {
+            char_boundary_fail(s, new_len);
+        }
         unsafe { self.set_len(new_len) };
     }
 
+    /// Truncate the [`CompactString`] to its first `char_count` [`char`]s.
+    ///
+    /// If the [`CompactString`] contains `char_count` or fewer [`char`]s, the call is a no-op.
+    ///
+    /// Calling this function does not change the capacity of the [`CompactString`].
+    ///
+    /// # Examples
+    ///
+    /// Basic usage:
+    ///
+    /// ```
+    /// # use compact_str::CompactString;
+    /// let mut s = CompactString::new("Grüße, Jürgen ❤");
+    /// s.truncate_chars(5);
+    /// assert_eq!(s, "Grüße");
+    /// ```
+    pub fn truncate_chars(&mut self, char_count: usize) {
+        if let Some((new_len, _)) = self.char_indices().nth(char_count) {
+            self.truncate(new_len);
+        }
+    }
+
     /// Converts a [`CompactString`] to a raw pointer.
     #[inline]
     pub fn as_ptr(&self) -> *const u8 {
@@ -1026,7 +2028,9 @@ impl CompactString {
     /// assert_eq!(s, "Hello, world!");
     /// ```
     pub fn insert_str(&mut self, idx: usize, string: &str) {
-        assert!(self.is_char_boundary(idx), "idx must lie on char boundary");
+        if !self.is_char_boundary(idx) {
+            char_boundary_fail(self.as_str(), idx);
+        }
 
         let new_len = self.len() + string.len();
         self.reserve(string.len());
@@ -1067,6 +2071,98 @@ impl CompactString {
         self.insert_str(idx, ch.encode_utf8(&mut [0; 4]));
     }
 
+    /// Insert a character at a [`char`] index, i.e. before the `char_idx`-th character.
+    ///
+    /// Inserting at the number of characters in the string appends the character.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `char_idx` is larger than the number of characters in the [`CompactString`].
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use compact_str::CompactString;
+    /// let mut s = CompactString::new("🦀🦀");
+    /// s.insert_at_char(1, '+');
+    /// assert_eq!(s, "🦀+🦀");
+    ///
+    /// s.insert_at_char(3, '!');
+    /// assert_eq!(s, "🦀+🦀!");
+    /// ```
+    #[track_caller]
+    pub fn insert_at_char(&mut self, char_idx: usize, ch: char) {
+        let mut indices = self.char_indices().map(|(idx, _)| idx).chain(Some(self.len()));
+        let byte_idx = match indices.nth(char_idx) {
+            Some(byte_idx) => byte_idx,
+            None => panic!(
+                "char index {} out of bounds, string has {} characters",
+                char_idx,
+                self.chars().count(),
+            ),
+        };
+        self.insert(byte_idx, ch);
+    }
+
+    /// Rotates the [`CompactString`] in place, such that the first `mid` bytes move to the end,
+    /// and the rest of the string moves to the front.
+    ///
+    /// This is like [`slice::rotate_left()`], but `mid` has to lie on a [`char`] boundary, so the
+    /// string stays valid UTF-8. If the [`CompactString`] was created from a `&'static str`, the
+    /// string first gets copied into a buffer we own.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `mid` is larger than the length of the string, or doesn't lie on a [`char`]
+    /// boundary.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use compact_str::CompactString;
+    /// let mut s = CompactString::new("🦀abc");
+    /// s.rotate_left(4);
+    /// assert_eq!(s, "abc🦀");
+    /// ```
+    #[track_caller]
+    pub fn rotate_left(&mut self, mid: usize) {
+        if !self.is_char_boundary(mid) {
+            char_boundary_fail(self.as_str(), mid);
+        }
+
+        let len = self.len();
+        // SAFETY: We just checked that `mid` is a char boundary, so both halves are valid UTF-8
+        // on their own, and swapping them keeps the string valid UTF-8.
+        unsafe { self.as_mut_bytes()[..len].rotate_left(mid) };
+    }
+
+    /// Rotates the [`CompactString`] in place, such that the last `k` bytes move to the front,
+    /// and the rest of the string moves to the end.
+    ///
+    /// This is like [`slice::rotate_right()`], see [`CompactString::rotate_left()`] for details.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `k` is larger than the length of the string, or if the string can't be split
+    /// `k` bytes before its end because that's not a [`char`] boundary.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use compact_str::CompactString;
+    /// let mut s = CompactString::new("abc🦀");
+    /// s.rotate_right(4);
+    /// assert_eq!(s, "🦀abc");
+    /// ```
+    #[track_caller]
+    pub fn rotate_right(&mut self, k: usize) {
+        let mid = match self.len().checked_sub(k) {
+            Some(mid) => mid,
+            None => char_boundary_fail(self.as_str(), k),
+        };
+        self.rotate_left(mid);
+    }
+
     /// Reduces the length of the [`CompactString`] to zero.
     ///
     /// Calling this function does not change the capacity of the [`CompactString`].
@@ -1240,10 +2336,11 @@ impl CompactString {
     /// assert_eq!(s, "b𝄞€");
     /// ```
     pub fn retain(&mut self, mut predicate: impl FnMut(char) -> bool) {
-        // We iterate over the string, and copy character by character.
+        // We iterate over the string, and copy character by character. If the `predicate` panics,
+        // the guard truncates the string to the characters we kept so far.
 
-        let s = self.as_mut_str();
-        let mut dest_idx = 0;
+        let mut guard = SetLenOnDrop { s: self, len: 0 };
+        let s = guard.s.as_mut_str();
         let mut src_idx = 0;
         while let Some(ch) = s[src_idx..].chars().next() {
             let ch_len = ch.len_utf8();
@@ -1251,15 +2348,83 @@ impl CompactString {
                 // SAFETY: We know that both indices are valid, and that we don't split a char.
                 unsafe {
                     let p = s.as_mut_ptr();
-                    core::ptr::copy(p.add(src_idx), p.add(dest_idx), ch_len);
+                    core::ptr::copy(p.add(src_idx), p.add(guard.len), ch_len);
                 }
-                dest_idx += ch_len;
+                guard.len += ch_len;
             }
             src_idx += ch_len;
         }
+    }
 
-        // SAFETY: We know that the index is a valid position to break the string.
-        unsafe { self.set_len(dest_idx) };
+    /// Retains only the characters specified by the predicate, which is also allowed to modify
+    /// each character.
+    ///
+    /// This is like [`CompactString::retain`], but the `predicate` is passed a mutable reference
+    /// to each character, like [`Vec::retain_mut`]. If the `predicate` returns `false`, then the
+    /// character gets removed, otherwise the possibly modified character is kept.
+    ///
+    /// Characters are updated in place, unless a kept character gets longer when encoded as UTF-8
+    /// and would overwrite characters we haven't visited yet, at which point the rest of the
+    /// string is collected into a new buffer.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use compact_str::CompactString;
+    /// let mut s = CompactString::from("Hello, World!");
+    ///
+    /// s.retain_mut(|c| {
+    ///     c.make_ascii_lowercase();
+    ///     c.is_alphabetic()
+    /// });
+    ///
+    /// assert_eq!(s, "helloworld");
+    /// ```
+    pub fn retain_mut(&mut self, mut predicate: impl FnMut(&mut char) -> bool) {
+        let len = self.len();
+        // If the `predicate` panics, the guard truncates the string to the characters we kept so
+        // far, since the bytes after them might be partially overwritten.
+        let mut guard = SetLenOnDrop { s: self, len: 0 };
+        // SAFETY: We only ever write complete chars, and only before `src_idx`, so the bytes we
+        // still have to read, and the bytes we keep, are always valid UTF-8.
+        let buf = unsafe { guard.s.as_mut_bytes() };
+        let mut src_idx = 0;
+        while src_idx < len {
+            // SAFETY: `src_idx` is always on a char boundary of the original string
+            let rest = unsafe { core::str::from_utf8_unchecked(&buf[src_idx..len]) };
+            let mut ch = rest.chars().next().unwrap();
+            src_idx += ch.len_utf8();
+
+            if !predicate(&mut ch) {
+                continue;
+            }
+
+            let ch_len = ch.len_utf8();
+            if guard.len + ch_len > src_idx {
+                // The modified char would overwrite chars we haven't visited yet, so we collect
+                // everything that's left into a new buffer
+                // SAFETY: `buf[..guard.len]` only contains the complete chars we kept, and
+                // `buf[src_idx..len]` hasn't been modified
+                let kept = unsafe { core::str::from_utf8_unchecked(&buf[..guard.len]) };
+                let rest = unsafe { core::str::from_utf8_unchecked(&buf[src_idx..len]) };
+
+                let mut result = CompactString::with_capacity(guard.len + ch_len + rest.len());
+                result.push_str(kept);
+                result.push(ch);
+                for mut ch in rest.chars() {
+                    if predicate(&mut ch) {
+                        result.push(ch);
+                    }
+                }
+
+                guard.len = result.len();
+                *guard.s = result;
+                return;
+            }
+
+            ch.encode_utf8(&mut buf[guard.len..guard.len + ch_len]);
+            guard.len += ch_len;
+        }
     }
 
     /// Decode a bytes slice as UTF-8 string, replacing any illegal codepoints
@@ -1515,51 +2680,279 @@ impl CompactString {
     /// synchronizing. I.e. if a byte in the input is dropped, all following data is broken.
     ///
     /// # Examples
-    ///
+    ///
+    /// ```
+    /// # use compact_str::CompactString;
+    /// // A "random" bit was flipped in the 4th byte:
+    /// const DANCING_MEN: &[u8] = b"\x3d\xd8\x6f\xfc\x0d\x20\x42\x26\x0f\xfe";
+    /// let dancing_men = CompactString::from_utf16le_lossy(DANCING_MEN);
+    /// assert_eq!(dancing_men, "�\u{fc6f}\u{200d}♂️");
+    /// ```
+    #[inline]
+    pub fn from_utf16le_lossy(v: impl AsRef<[u8]>) -> Self {
+        CompactString::from_utf16x_lossy(v.as_ref(), u16::from_le, u16::from_le_bytes)
+    }
+
+    /// Lossy decode a slice of bytes as UTF-16 encoded string, in big endian.
+    ///
+    /// In this context "lossy" means that any broken characters in the input are replaced by the
+    /// \<REPLACEMENT CHARACTER\> `'�'`. Please notice that, unlike UTF-8, UTF-16 is not self
+    /// synchronizing. I.e. if a byte in the input is dropped, all following data is broken.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use compact_str::CompactString;
+    /// // A "random" bit was flipped in the 9th byte:
+    /// const DANCING_WOMEN: &[u8] = b"\xd8\x3d\xdc\x6f\x20\x0d\x26\x40\xde\x0f";
+    /// let dancing_women = CompactString::from_utf16be_lossy(DANCING_WOMEN);
+    /// assert_eq!(dancing_women, "👯\u{200d}♀�");
+    /// ```
+    #[inline]
+    pub fn from_utf16be_lossy(v: impl AsRef<[u8]>) -> Self {
+        CompactString::from_utf16x_lossy(v.as_ref(), u16::from_be, u16::from_be_bytes)
+    }
+
+    /// Formats an `f32` with exactly `precision` digits after the decimal point, the same as
+    /// `format!("{:.precision$}", value)`.
+    ///
+    /// Unlike [`ToCompactString`], which uses the shortest representation that roundtrips, the
+    /// output is rounded to a fixed precision. The string is written inline, and only heap
+    /// allocated if it doesn't fit.
+    ///
+    /// # Examples
+    /// ```
+    /// # use compact_str::CompactString;
+    /// let s = CompactString::from_f32_precision(1.5, 3);
+    /// assert_eq!(s, "1.500");
+    /// ```
+    #[inline]
+    #[track_caller]
+    pub fn from_f32_precision(value: f32, precision: usize) -> Self {
+        CompactString(Repr::from_f32_precision(value, precision).unwrap_with_msg())
+    }
+
+    /// Formats an `f64` with exactly `precision` digits after the decimal point, the same as
+    /// `format!("{:.precision$}", value)`.
+    ///
+    /// Unlike [`ToCompactString`], which uses the shortest representation that roundtrips, the
+    /// output is rounded to a fixed precision. The string is written inline, and only heap
+    /// allocated if it doesn't fit.
+    ///
+    /// # Examples
+    /// ```
+    /// # use compact_str::CompactString;
+    /// let price = CompactString::from_f64_precision(19.999, 2);
+    /// assert_eq!(price, "20.00");
+    /// assert_eq!(price, format!("{:.2}", 19.999));
+    /// ```
+    #[inline]
+    #[track_caller]
+    pub fn from_f64_precision(value: f64, precision: usize) -> Self {
+        CompactString(Repr::from_f64_precision(value, precision).unwrap_with_msg())
+    }
+
+    /// Formats a signed integer with `sep` inserted between every group of three digits, e.g.
+    /// `1,234,567`.
+    ///
+    /// The string is written inline, and only heap allocated if it doesn't fit.
+    ///
+    /// # Examples
+    /// ```
+    /// # use compact_str::CompactString;
+    /// assert_eq!(CompactString::from_int_grouped(-1234567, ','), "-1,234,567");
+    /// assert_eq!(CompactString::from_int_grouped(999, ','), "999");
+    /// assert_eq!(CompactString::from_int_grouped(1_000_000, '_'), "1_000_000");
+    /// ```
+    #[inline]
+    #[track_caller]
+    pub fn from_int_grouped(value: i128, sep: char) -> Self {
+        CompactString(Repr::from_int_grouped(value, sep).unwrap_with_msg())
+    }
+
+    /// Formats an unsigned integer with `sep` inserted between every group of three digits, e.g.
+    /// `1,234,567`.
+    ///
+    /// The string is written inline, and only heap allocated if it doesn't fit.
+    ///
+    /// # Examples
+    /// ```
+    /// # use compact_str::CompactString;
+    /// let max = CompactString::from_uint_grouped(u64::MAX.into(), ',');
+    /// assert_eq!(max, "18,446,744,073,709,551,615");
+    /// assert_eq!(CompactString::from_uint_grouped(42, ','), "42");
+    /// ```
+    #[inline]
+    #[track_caller]
+    pub fn from_uint_grouped(value: u128, sep: char) -> Self {
+        CompactString(Repr::from_uint_grouped(value, sep).unwrap_with_msg())
+    }
+
+    /// Creates a [`CompactString`] by repeatedly calling `f`, pushing each returned [`char`],
+    /// until it returns `None`.
+    ///
+    /// The string is built without an intermediate collection, and is only heap allocated once it
+    /// no longer fits inline.
+    ///
+    /// # Examples
+    /// ```
+    /// # use compact_str::CompactString;
+    /// let mut n = 0;
+    /// let digits = CompactString::from_fn(|| {
+    ///     n += 1;
+    ///     char::from_digit(n, 10)
+    /// });
+    ///
+    /// assert_eq!(digits, "123456789");
+    /// ```
+    #[inline]
+    pub fn from_fn<F: FnMut() -> Option<char>>(f: F) -> Self {
+        core::iter::from_fn(f).collect()
+    }
+
+    /// Convert the [`CompactString`] into a [`String`].
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use compact_str::CompactString;
+    /// let s = CompactString::new("Hello world");
+    /// let s = s.into_string();
+    /// assert_eq!(s, "Hello world");
+    /// ```
+    pub fn into_string(self) -> String {
+        self.0.into_string()
+    }
+
+    /// Consumes the [`CompactString`], returning an iterator over its bytes.
+    ///
+    /// This is like `String::into_bytes().into_iter()`, but without converting into a
+    /// [`Vec`](alloc::vec::Vec) first. The underlying buffer is freed once the iterator is
+    /// dropped.
+    ///
+    /// # Examples
+    /// ```
+    /// # use compact_str::CompactString;
+    /// let s = CompactString::new("ü!");
+    /// let mut bytes = s.into_bytes_iter();
+    ///
+    /// assert_eq!(bytes.next(), Some(0xC3));
+    /// assert_eq!(bytes.as_slice(), &[0xBC, b'!']);
+    /// assert_eq!(bytes.next_back(), Some(b'!'));
+    /// assert_eq!(bytes.next(), Some(0xBC));
+    /// assert_eq!(bytes.next(), None);
+    /// ```
+    #[inline]
+    pub fn into_bytes_iter(self) -> IntoBytes {
+        let end = self.len();
+        IntoBytes {
+            compact_string: self,
+            start: 0,
+            end,
+        }
+    }
+
+    /// Converts the [`CompactString`] into a [`Box<str>`].
+    ///
+    /// The returned allocation is exactly as long as the string, so any excess capacity is
+    /// dropped. Heap allocated strings re-use their existing buffer, shrinking it if needed,
+    /// whereas inline strings are copied into a new allocation.
+    ///
+    /// # Examples
+    /// ```
+    /// # use compact_str::CompactString;
+    /// let s = CompactString::new("hello");
+    /// let b: Box<str> = s.into_boxed_str();
+    ///
+    /// assert_eq!(&*b, "hello");
+    /// ```
+    #[inline]
+    pub fn into_boxed_str(self) -> Box<str> {
+        if self.is_heap_allocated() {
+            self.into_string().into_boxed_str()
+        } else {
+            Box::from(self.as_str())
+        }
+    }
+
+    /// Converts the [`CompactString`] into an [`Arc<str>`](alloc::sync::Arc).
+    ///
+    /// An `Arc<str>` stores its reference counts in the same allocation as the string, so the
+    /// contents are always copied into a new allocation, even if the [`CompactString`] is heap
+    /// allocated.
+    ///
+    /// # Examples
     /// ```
     /// # use compact_str::CompactString;
-    /// // A "random" bit was flipped in the 4th byte:
-    /// const DANCING_MEN: &[u8] = b"\x3d\xd8\x6f\xfc\x0d\x20\x42\x26\x0f\xfe";
-    /// let dancing_men = CompactString::from_utf16le_lossy(DANCING_MEN);
-    /// assert_eq!(dancing_men, "�\u{fc6f}\u{200d}♂️");
+    /// let s = CompactString::new("hello");
+    /// let arc = s.into_arc_str();
+    ///
+    /// assert_eq!(&*arc, "hello");
     /// ```
+    #[rustversion::since(1.60)]
+    #[cfg(target_has_atomic = "ptr")]
     #[inline]
-    pub fn from_utf16le_lossy(v: impl AsRef<[u8]>) -> Self {
-        CompactString::from_utf16x_lossy(v.as_ref(), u16::from_le, u16::from_le_bytes)
+    pub fn into_arc_str(self) -> alloc::sync::Arc<str> {
+        alloc::sync::Arc::from(self.as_str())
     }
 
-    /// Lossy decode a slice of bytes as UTF-16 encoded string, in big endian.
+    /// Creates a [`CompactString`] from an [`Arc<str>`](alloc::sync::Arc).
     ///
-    /// In this context "lossy" means that any broken characters in the input are replaced by the
-    /// \<REPLACEMENT CHARACTER\> `'�'`. Please notice that, unlike UTF-8, UTF-16 is not self
-    /// synchronizing. I.e. if a byte in the input is dropped, all following data is broken.
+    /// The contents are copied, so the [`CompactString`] can be mutated without affecting any
+    /// other clones of the `Arc`. Short strings are stored inline.
     ///
     /// # Examples
-    ///
     /// ```
     /// # use compact_str::CompactString;
-    /// // A "random" bit was flipped in the 9th byte:
-    /// const DANCING_WOMEN: &[u8] = b"\xd8\x3d\xdc\x6f\x20\x0d\x26\x40\xde\x0f";
-    /// let dancing_women = CompactString::from_utf16be_lossy(DANCING_WOMEN);
-    /// assert_eq!(dancing_women, "👯\u{200d}♀�");
+    /// # use std::sync::Arc;
+    /// let arc: Arc<str> = Arc::from("hello");
+    /// let mut s = CompactString::from_arc_str(Arc::clone(&arc));
+    /// s.push_str(" world");
+    ///
+    /// assert_eq!(s, "hello world");
+    /// assert_eq!(&*arc, "hello");
     /// ```
+    #[rustversion::since(1.60)]
+    #[cfg(target_has_atomic = "ptr")]
     #[inline]
-    pub fn from_utf16be_lossy(v: impl AsRef<[u8]>) -> Self {
-        CompactString::from_utf16x_lossy(v.as_ref(), u16::from_be, u16::from_be_bytes)
+    #[track_caller]
+    pub fn from_arc_str(arc: alloc::sync::Arc<str>) -> Self {
+        CompactString::new(&*arc)
     }
 
-    /// Convert the [`CompactString`] into a [`String`].
+    /// Consumes and leaks the [`CompactString`], returning a mutable reference to the contents,
+    /// `&'a mut str`.
     ///
-    /// # Examples
+    /// This mirrors [`String::leak`]. The caller has free choice over the returned lifetime,
+    /// including `'static`. Dropping the returned reference will cause a memory leak, since the
+    /// underlying buffer is never freed.
+    ///
+    /// Inline strings live within the [`CompactString`] itself, so they are first copied into a
+    /// new heap allocation. Strings that are already heap allocated leak their existing buffer,
+    /// including any excess capacity. If you want to leak exactly the length of the string,
+    /// call [`CompactString::shrink_to_fit()`] first.
     ///
+    /// This function is mainly useful for data that lives for the remainder of the program's
+    /// life.
+    ///
+    /// # Examples
     /// ```
     /// # use compact_str::CompactString;
-    /// let s = CompactString::new("Hello world");
-    /// let s = s.into_string();
-    /// assert_eq!(s, "Hello world");
+    /// let s = CompactString::new("hello world");
+    /// let leaked: &'static mut str = s.leak();
+    ///
+    /// leaked.make_ascii_uppercase();
+    /// assert_eq!(leaked, "HELLO WORLD");
     /// ```
-    pub fn into_string(self) -> String {
-        self.0.into_string()
+    #[inline]
+    pub fn leak<'a>(self) -> &'a mut str {
+        let mut s = mem::ManuallyDrop::new(self.into_string());
+        let len = s.len();
+        let ptr = s.as_mut_ptr();
+
+        // SAFETY: The `String` is never dropped, so its buffer is valid for the rest of the
+        // program, and it contains `len` bytes of valid UTF-8.
+        unsafe { core::str::from_utf8_unchecked_mut(slice::from_raw_parts_mut(ptr, len)) }
     }
 
     /// Convert a [`String`] into a [`CompactString`] _without inlining_.
@@ -1741,6 +3134,35 @@ impl CompactString {
         Self::from_str_to_lowercase(self.as_str())
     }
 
+    /// Returns the lowercase equivalent of this string, borrowing the [`CompactString`] if it's
+    /// already lowercase.
+    ///
+    /// This is like [`CompactString::to_lowercase()`], but for data that's usually normalized
+    /// already, it avoids copying the string. A new string is only allocated if converting
+    /// changes at least one character.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use std::borrow::Cow;
+    /// use compact_str::CompactString;
+    ///
+    /// let s = CompactString::new("hello, wörld");
+    /// assert!(matches!(s.to_lowercase_cow(), Cow::Borrowed("hello, wörld")));
+    ///
+    /// let s = CompactString::new("Hello, Wörld");
+    /// assert!(matches!(s.to_lowercase_cow(), Cow::Owned(owned) if owned == "hello, wörld"));
+    /// ```
+    pub fn to_lowercase_cow(&self) -> Cow<'_, str> {
+        // a char that maps to itself is never changed, the only contextual mapping is for 'Σ',
+        // which is always changed
+        if self.chars().all(|c| c.to_lowercase().eq(Some(c))) {
+            Cow::Borrowed(self.as_str())
+        } else {
+            Cow::Owned(self.to_lowercase().into_string())
+        }
+    }
+
     /// Returns the lowercase equivalent of this string slice, as a new [`CompactString`].
     ///
     /// 'Lowercase' is defined according to the terms of the Unicode Derived Core Property
@@ -1863,6 +3285,31 @@ impl CompactString {
         Self::from_str_to_uppercase(self.as_str())
     }
 
+    /// Returns the uppercase equivalent of this string, borrowing the [`CompactString`] if it's
+    /// already uppercase.
+    ///
+    /// See [`CompactString::to_lowercase_cow()`] for details.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use std::borrow::Cow;
+    /// use compact_str::CompactString;
+    ///
+    /// let s = CompactString::new("HELLO, WÖRLD");
+    /// assert!(matches!(s.to_uppercase_cow(), Cow::Borrowed("HELLO, WÖRLD")));
+    ///
+    /// let s = CompactString::new("tschüß");
+    /// assert!(matches!(s.to_uppercase_cow(), Cow::Owned(owned) if owned == "TSCHÜSS"));
+    /// ```
+    pub fn to_uppercase_cow(&self) -> Cow<'_, str> {
+        if self.chars().all(|c| c.to_uppercase().eq(Some(c))) {
+            Cow::Borrowed(self.as_str())
+        } else {
+            Cow::Owned(self.to_uppercase().into_string())
+        }
+    }
+
     /// Returns the uppercase equivalent of this string slice, as a new [`CompactString`].
     ///
     /// 'Uppercase' is defined according to the terms of the Unicode Derived Core Property
@@ -1912,6 +3359,35 @@ impl CompactString {
 
         out
     }
+
+    /// Returns a copy of this string with each run of whitespace replaced by a single space, and
+    /// leading and trailing whitespace removed.
+    ///
+    /// 'Whitespace' is defined according to the terms of the Unicode Derived Core Property
+    /// `White_Space`, the same as [`str::split_whitespace`].
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use compact_str::CompactString;
+    /// let s = CompactString::new("  hello \t\n  world  ");
+    ///
+    /// assert_eq!("hello world", s.collapse_whitespace());
+    /// ```
+    #[must_use = "this returns the collapsed string as a new CompactString, \
+                  without modifying the original"]
+    pub fn collapse_whitespace(&self) -> Self {
+        let mut out = CompactString::default();
+
+        for word in self.split_whitespace() {
+            if !out.is_empty() {
+                out.push(' ');
+            }
+            out.push_str(word);
+        }
+
+        out
+    }
 }
 
 /// Converts the bytes while the bytes are still ascii.
@@ -1962,6 +3438,8 @@ fn convert_while_ascii(b: &[u8], convert: fn(&u8) -> u8) -> CompactString {
     out
 }
 
+/// Cloning a heap allocated [`CompactString`] always copies it into a new buffer that's only as
+/// large as the string, so a clone of an over-allocated string is suitable for long-term storage.
 impl Clone for CompactString {
     #[inline]
     fn clone(&self) -> Self {
@@ -2059,6 +3537,45 @@ impl<'a> PartialEq<CompactString> for Cow<'a, str> {
     }
 }
 
+impl PartialEq<[u8]> for CompactString {
+    fn eq(&self, other: &[u8]) -> bool {
+        self.as_bytes() == other
+    }
+}
+
+impl PartialEq<CompactString> for [u8] {
+    fn eq(&self, other: &CompactString) -> bool {
+        self == other.as_bytes()
+    }
+}
+
+impl PartialEq<CompactString> for &[u8] {
+    fn eq(&self, other: &CompactString) -> bool {
+        *self == other.as_bytes()
+    }
+}
+
+#[cfg(feature = "std")]
+impl PartialEq<OsStr> for CompactString {
+    fn eq(&self, other: &OsStr) -> bool {
+        other.to_str() == Some(self.as_str())
+    }
+}
+
+#[cfg(feature = "std")]
+impl PartialEq<CompactString> for OsStr {
+    fn eq(&self, other: &CompactString) -> bool {
+        self.to_str() == Some(other.as_str())
+    }
+}
+
+#[cfg(feature = "std")]
+impl PartialEq<CompactString> for &OsStr {
+    fn eq(&self, other: &CompactString) -> bool {
+        self.to_str() == Some(other.as_str())
+    }
+}
+
 impl Ord for CompactString {
     fn cmp(&self, other: &Self) -> Ordering {
         self.as_str().cmp(other.as_str())
@@ -2121,6 +3638,54 @@ impl From<Box<str>> for CompactString {
     }
 }
 
+impl<'a> From<&'a Box<str>> for CompactString {
+    #[inline]
+    #[track_caller]
+    fn from(b: &'a Box<str>) -> Self {
+        CompactString::new(b)
+    }
+}
+
+/// Validates that the array of bytes is UTF-8, the same as [`CompactString::from_utf8`].
+///
+/// # Examples
+/// ```
+/// # use compact_str::CompactString;
+/// use core::convert::TryFrom;
+///
+/// let compact = CompactString::try_from([104, 105]).unwrap();
+/// assert_eq!(compact, "hi");
+///
+/// assert!(CompactString::try_from([104, 105, 255]).is_err());
+/// ```
+impl<const N: usize> TryFrom<[u8; N]> for CompactString {
+    type Error = Utf8Error;
+
+    #[inline]
+    #[track_caller]
+    fn try_from(buf: [u8; N]) -> Result<Self, Self::Error> {
+        CompactString::from_utf8(buf)
+    }
+}
+
+/// Implements `From<$t> for CompactString` for numeric types, formatting them the same way as
+/// [`ToCompactString`] does
+macro_rules! impl_from_num {
+    ($($t:ty),*) => {
+        $(
+            impl From<$t> for CompactString {
+                #[inline]
+                #[track_caller]
+                fn from(n: $t) -> Self {
+                    CompactString(n.into_repr().unwrap_with_msg())
+                }
+            }
+        )*
+    };
+}
+
+impl_from_num!(u8, u16, u32, u64, u128, usize, i8, i16, i32, i64, i128, isize, f32, f64);
+
 impl From<CompactString> for String {
     #[inline]
     fn from(s: CompactString) -> Self {
@@ -2150,7 +3715,7 @@ impl<'a> From<&'a CompactString> for Cow<'a, str> {
 #[cfg(target_has_atomic = "ptr")]
 impl From<CompactString> for alloc::sync::Arc<str> {
     fn from(value: CompactString) -> Self {
-        Self::from(value.as_str())
+        value.into_arc_str()
     }
 }
 
@@ -2181,7 +3746,7 @@ impl From<CompactString> for Box<dyn std::error::Error + Send + Sync> {
         // Purposefully skip printing "StringError(..)"
         impl fmt::Debug for StringError {
             fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
-                fmt::Debug::fmt(&self.0, f)
+                fmt::Debug::fmt(self.0.as_str(), f)
             }
         }
 
@@ -2199,12 +3764,9 @@ impl From<CompactString> for Box<dyn std::error::Error> {
 }
 
 impl From<CompactString> for Box<str> {
+    #[inline]
     fn from(value: CompactString) -> Self {
-        if value.is_heap_allocated() {
-            value.into_string().into_boxed_str()
-        } else {
-            Box::from(value.as_str())
-        }
+        value.into_boxed_str()
     }
 }
 
@@ -2246,9 +3808,35 @@ impl FromStr for CompactString {
     }
 }
 
+/// Formats the [`CompactString`] like a [`str`].
+///
+/// With the alternate flag, i.e. `{:#?}`, the length, capacity, and whether or not the string is
+/// heap allocated are printed as well, which can be useful when diagnosing memory usage.
+///
+/// # Examples
+/// ```
+/// # use compact_str::CompactString;
+/// let s = CompactString::new("hello");
+///
+/// assert_eq!(format!("{s:?}"), "\"hello\"");
+///
+/// let pretty = format!("{s:#?}");
+/// assert!(pretty.starts_with("CompactString {"));
+/// assert!(pretty.contains("len: 5"));
+/// assert!(pretty.contains("heap_allocated: false"));
+/// ```
 impl fmt::Debug for CompactString {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
-        fmt::Debug::fmt(self.as_str(), f)
+        if f.alternate() {
+            f.debug_struct("CompactString")
+                .field("string", &self.as_str())
+                .field("len", &self.len())
+                .field("capacity", &self.capacity())
+                .field("heap_allocated", &self.is_heap_allocated())
+                .finish()
+        } else {
+            fmt::Debug::fmt(self.as_str(), f)
+        }
     }
 }
 
@@ -2373,6 +3961,17 @@ impl Extend<CompactString> for String {
 
 impl Extend<CompactString> for CompactString {
     fn extend<T: IntoIterator<Item = CompactString>>(&mut self, iter: T) {
+        let mut iter = iter.into_iter();
+
+        if self.is_empty() {
+            match iter.next() {
+                // We'd need to allocate a new buffer anyway, so adopt the one from `s`
+                Some(s) if s.len() > self.capacity() => *self = s,
+                Some(s) => self.push_str(&s),
+                None => return,
+            }
+        }
+
         for s in iter {
             self.push_str(&s);
         }
@@ -2385,6 +3984,37 @@ impl<'a> Extend<CompactString> for Cow<'a, str> {
     }
 }
 
+impl IntoIterator for CompactString {
+    type Item = char;
+    type IntoIter = IntoChars;
+
+    /// Consumes the [`CompactString`], returning an iterator over its [`char`]s.
+    ///
+    /// The underlying buffer is freed once the iterator is dropped.
+    ///
+    /// # Examples
+    /// ```
+    /// # use compact_str::CompactString;
+    /// let s = CompactString::new("🦀ab");
+    /// let mut chars = s.into_iter();
+    ///
+    /// assert_eq!(chars.next(), Some('🦀'));
+    /// assert_eq!(chars.as_str(), "ab");
+    /// assert_eq!(chars.next_back(), Some('b'));
+    /// assert_eq!(chars.next(), Some('a'));
+    /// assert_eq!(chars.next(), None);
+    /// ```
+    #[inline]
+    fn into_iter(self) -> Self::IntoIter {
+        let end = self.len();
+        IntoChars {
+            compact_string: self,
+            start: 0,
+            end,
+        }
+    }
+}
+
 impl fmt::Write for CompactString {
     fn write_str(&mut self, s: &str) -> fmt::Result {
         self.push_str(s);
@@ -2536,6 +4166,165 @@ impl DoubleEndedIterator for Drain<'_> {
 
 impl FusedIterator for Drain<'_> {}
 
+/// Sets the length of a [`CompactString`] when dropped, so a panic in the middle of an operation
+/// that moves characters around can't leave invalid UTF-8 behind.
+struct SetLenOnDrop<'a> {
+    s: &'a mut CompactString,
+    len: usize,
+}
+
+impl Drop for SetLenOnDrop<'_> {
+    fn drop(&mut self) {
+        // SAFETY: Users of this guard only ever set `len` to a char boundary, with complete chars
+        // before it.
+        unsafe { self.s.set_len(self.len) };
+    }
+}
+
+/// An owning iterator over the [`char`]s of a [`CompactString`].
+///
+/// This struct is created by the [`into_iter()`] method on [`CompactString`].
+///
+/// [`into_iter()`]: CompactString::into_iter
+#[derive(Clone)]
+#[must_use = "iterators are lazy and do nothing unless consumed"]
+pub struct IntoChars {
+    compact_string: CompactString,
+    start: usize,
+    end: usize,
+}
+
+impl IntoChars {
+    /// The remaining, unconsumed characters of the string.
+    #[inline]
+    pub fn as_str(&self) -> &str {
+        // SAFETY: `start` and `end` are only ever moved by whole characters, so they are in bounds
+        // and lie on char boundaries
+        unsafe { self.compact_string.get_unchecked(self.start..self.end) }
+    }
+}
+
+impl fmt::Debug for IntoChars {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_tuple("IntoChars").field(&self.as_str()).finish()
+    }
+}
+
+impl Iterator for IntoChars {
+    type Item = char;
+
+    #[inline]
+    fn next(&mut self) -> Option<char> {
+        let ch = self.as_str().chars().next()?;
+        self.start += ch.len_utf8();
+        Some(ch)
+    }
+
+    #[inline]
+    fn count(self) -> usize {
+        self.as_str().chars().count()
+    }
+
+    #[inline]
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.as_str().chars().size_hint()
+    }
+
+    #[inline]
+    fn last(mut self) -> Option<char> {
+        self.next_back()
+    }
+}
+
+impl DoubleEndedIterator for IntoChars {
+    #[inline]
+    fn next_back(&mut self) -> Option<char> {
+        let ch = self.as_str().chars().next_back()?;
+        self.end -= ch.len_utf8();
+        Some(ch)
+    }
+}
+
+impl FusedIterator for IntoChars {}
+
+/// An owning iterator over the bytes of a [`CompactString`].
+///
+/// This struct is created by the [`into_bytes_iter()`] method on [`CompactString`].
+///
+/// [`into_bytes_iter()`]: CompactString::into_bytes_iter
+#[derive(Clone)]
+#[must_use = "iterators are lazy and do nothing unless consumed"]
+pub struct IntoBytes {
+    compact_string: CompactString,
+    start: usize,
+    end: usize,
+}
+
+impl IntoBytes {
+    /// The remaining, unconsumed bytes of the string.
+    #[inline]
+    pub fn as_slice(&self) -> &[u8] {
+        &self.compact_string.as_bytes()[self.start..self.end]
+    }
+}
+
+impl fmt::Debug for IntoBytes {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_tuple("IntoBytes").field(&self.as_slice()).finish()
+    }
+}
+
+impl Iterator for IntoBytes {
+    type Item = u8;
+
+    #[inline]
+    fn next(&mut self) -> Option<u8> {
+        let byte = *self.as_slice().first()?;
+        self.start += 1;
+        Some(byte)
+    }
+
+    #[inline]
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let len = self.len();
+        (len, Some(len))
+    }
+
+    #[inline]
+    fn count(self) -> usize {
+        self.len()
+    }
+
+    #[inline]
+    fn nth(&mut self, n: usize) -> Option<u8> {
+        self.start = self.start.saturating_add(n).min(self.end);
+        self.next()
+    }
+
+    #[inline]
+    fn last(mut self) -> Option<u8> {
+        self.next_back()
+    }
+}
+
+impl DoubleEndedIterator for IntoBytes {
+    #[inline]
+    fn next_back(&mut self) -> Option<u8> {
+        let byte = *self.as_slice().last()?;
+        self.end -= 1;
+        Some(byte)
+    }
+}
+
+impl ExactSizeIterator for IntoBytes {
+    #[inline]
+    fn len(&self) -> usize {
+        self.end - self.start
+    }
+}
+
+impl FusedIterator for IntoBytes {}
+
 /// A possible error value if allocating or resizing a [`CompactString`] failed.
 #[derive(Debug, Clone, Copy, PartialEq)]
 pub struct ReserveError(());
@@ -2620,4 +4409,16 @@ fn unwrap_with_msg_fail<E: fmt::Display>(error: E) -> ! {
     panic!("{error}")
 }
 
+/// Panics with the same message `str` uses when sliced at an invalid `idx`, e.g. "byte index 1 is
+/// not a char boundary; it is inside '🦄' (bytes 0..4) of ..."
+#[inline(never)]
+#[cold]
+#[track_caller]
+fn char_boundary_fail(s: &str, idx: usize) -> ! {
+    // let `core` format the message, so it stays identical to std's, including how long strings
+    // get truncated and the wording for indices that are out of bounds
+    let _ = &s[idx..];
+    unreachable!("byte index {idx} is a char boundary of `{s}`")
+}
+
 static_assertions::assert_eq_size!(CompactString, String);
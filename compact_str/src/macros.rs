@@ -1,3 +1,8 @@
+use crate::{
+    CompactString,
+    ToCompactString,
+};
+
 /// Creates a `CompactString` using interpolation of runtime expressions.
 ///
 /// The first argument `format_compact!` receives is a format string.
@@ -27,12 +32,85 @@
 #[macro_export]
 macro_rules! format_compact {
     ($($arg:tt)*) => {
-        $crate::ToCompactString::to_compact_string(&$crate::core::format_args!($($arg)*))
+        $crate::format_compact($crate::core::format_args!($($arg)*))
+    }
+}
+
+/// Creates a `CompactString` from a string literal at compile time.
+///
+/// The literal is stored inline if it fits, otherwise the `CompactString` points at the
+/// `&'static str`, so this never heap allocates. Both are decided at compile time, see
+/// [`CompactString::from_static_str`]. The macro can be used in `const` and `static` items
+/// too.
+///
+/// # Examples
+/// ```
+/// use compact_str::{compact, CompactString};
+///
+/// const GREETING: CompactString = compact!("hello");
+/// assert_eq!(GREETING, "hello");
+///
+/// let long = compact!("this literal is too long to be stored inline");
+/// assert_eq!(long.as_static_str(), Some("this literal is too long to be stored inline"));
+/// assert!(!long.is_heap_allocated());
+/// ```
+#[macro_export]
+macro_rules! compact {
+    ($lit:literal) => {{
+        const COMPACT: $crate::CompactString = $crate::CompactString::from_static_str($lit);
+        COMPACT
+    }};
+}
+
+/// Asserts that a `CompactString` is not heap allocated, when debug assertions are enabled.
+///
+/// This is a shorthand for [`CompactString::assert_inline`], so performance sensitive code can
+/// catch regressions where a value unexpectedly exceeds the inline limit. Like
+/// [`debug_assert!`], the check is skipped in release builds.
+///
+/// # Examples
+/// ```
+/// use compact_str::{debug_assert_inline, CompactString};
+///
+/// let id = CompactString::new("user-1234");
+/// debug_assert_inline!(id);
+/// ```
+#[macro_export]
+macro_rules! debug_assert_inline {
+    ($compact:expr $(,)?) => {
+        $crate::CompactString::assert_inline(&$compact)
+    };
+}
+
+/// Takes an [`Arguments`](core::fmt::Arguments) struct and returns the resulting formatted
+/// [`CompactString`].
+///
+/// This is the function [`format_compact!`] expands to. If the arguments are a plain string
+/// literal with nothing to format, the string is copied directly, without going through the
+/// formatting machinery.
+///
+/// # Panics
+///
+/// Panics if a formatting trait implementation returns an error.
+///
+/// # Examples
+/// ```
+/// let compact = compact_str::format_compact(format_args!("{}, {}!", "Hello", "world"));
+/// assert_eq!(compact, "Hello, world!");
+/// ```
+#[inline]
+#[track_caller]
+pub fn format_compact(args: core::fmt::Arguments<'_>) -> CompactString {
+    match args.as_str() {
+        Some(s) => CompactString::new(s),
+        None => args.to_compact_string(),
     }
 }
 
 #[cfg(test)]
 mod tests {
+    use alloc::string::ToString;
+
     #[test]
     fn test_macros() {
         assert_eq!(format_compact!("2"), "2");
@@ -41,4 +119,69 @@ mod tests {
         assert!(!format_compact!("2").is_heap_allocated());
         assert!(!format_compact!("{}", 2).is_heap_allocated());
     }
+
+    #[test]
+    fn test_matches_format() {
+        let (a, b) = ("hello", 42);
+        assert_eq!(format_compact!("{}{}", a, b), format!("{}{}", a, b));
+        assert!(!format_compact!("{}{}", a, b).is_heap_allocated());
+
+        let long = "a string that is too long to be inlined";
+        let compact = format_compact!("{} {:?} {:>5}", long, a, b);
+        assert_eq!(compact, format!("{} {:?} {:>5}", long, a, b));
+        assert!(compact.is_heap_allocated());
+
+        // nothing to format, the literal gets copied as is
+        let compact = format_compact!("a literal that is too long to be inlined, {{escaped}}");
+        assert_eq!(compact, "a literal that is too long to be inlined, {escaped}");
+        assert_eq!(compact.capacity(), compact.len());
+    }
+
+    #[test]
+    fn test_format_compact_fn() {
+        let compact = crate::format_compact(format_args!("{}-{}", 1, 2.5));
+        assert_eq!(compact, "1-2.5");
+        assert_eq!(compact, format_args!("{}-{}", 1, 2.5).to_string());
+    }
+
+    #[test]
+    fn test_debug_assert_inline() {
+        let short = crate::CompactString::new("hello");
+        debug_assert_inline!(short);
+        debug_assert_inline!(&short);
+
+        // static strs are never heap allocated
+        debug_assert_inline!(compact!("a literal that is too long to be inlined"));
+    }
+
+    #[test]
+    #[cfg(debug_assertions)]
+    #[should_panic(expected = "expected CompactString to be inline")]
+    fn test_debug_assert_inline_heap() {
+        let long = crate::CompactString::new("a string that is too long to be inlined");
+        debug_assert_inline!(long);
+    }
+
+    #[test]
+    fn test_compact() {
+        const SHORT: crate::CompactString = compact!("hello");
+        static LONG: crate::CompactString = compact!("a literal that is too long to be inlined");
+
+        assert_eq!(SHORT, "hello");
+        assert_eq!(SHORT.as_static_str(), None);
+        assert!(!SHORT.is_heap_allocated());
+
+        assert_eq!(LONG, "a literal that is too long to be inlined");
+        assert_eq!(LONG.as_static_str(), Some("a literal that is too long to be inlined"));
+        assert!(!LONG.is_heap_allocated());
+
+        // 24 bytes, our max inline size on 64-bit arches
+        let max = compact!("exactly 24 bytes of text");
+        assert_eq!(max.len() <= crate::repr::MAX_SIZE, max.as_static_str().is_none());
+
+        let mut empty = compact!("");
+        assert!(empty.is_empty());
+        empty.push_str("can be mutated");
+        assert_eq!(empty, "can be mutated");
+    }
 }
@@ -16,7 +16,19 @@ use crate::{
 };
 
 /// The minimum size we'll allocate on the heap is one usize larger than our max inline size
-const MIN_HEAP_SIZE: usize = MAX_SIZE + mem::size_of::<usize>();
+pub const MIN_HEAP_SIZE: usize = MAX_SIZE + mem::size_of::<usize>();
+
+/// The largest capacity we'll attempt to allocate. A [`Layout`] can't be larger than `isize::MAX`
+/// bytes, which includes the `usize` we might store the capacity in and any padding, and on 64-bit
+/// arches the capacity always has to fit in a [`Capacity`].
+const MAX_CAPACITY: usize = {
+    let max = isize::MAX as usize - 2 * mem::size_of::<usize>();
+    if cfg!(target_pointer_width = "64") && super::capacity::MAX_VALUE < max {
+        super::capacity::MAX_VALUE
+    } else {
+        max
+    }
+};
 
 const UNKNOWN: usize = 0;
 pub type StrBuffer = [u8; UNKNOWN];
@@ -28,7 +40,9 @@ pub type StrBuffer = [u8; UNKNOWN];
 #[inline(always)]
 pub fn amortized_growth(cur_len: usize, additional: usize) -> usize {
     let required = cur_len.saturating_add(additional);
-    let amortized = cur_len.saturating_mul(3) / 2;
+    // note: `cur_len + cur_len / 2` can't overflow before we clamp it, unlike `3 * cur_len / 2`.
+    // We also never grow past the largest capacity we can allocate, unless it's required
+    let amortized = cur_len.saturating_add(cur_len / 2).min(MAX_CAPACITY);
     amortized.max(required)
 }
 
@@ -66,15 +80,16 @@ impl HeapBuffer {
         Ok(HeapBuffer { ptr, len, cap })
     }
 
-    /// Create a [`HeapBuffer`] with `text` that has _at least_ `additional` bytes of capacity
+    /// Create a [`HeapBuffer`] with `text` that has _at least_ `capacity` bytes of capacity
     ///
-    /// To prevent frequent re-allocations, this method will create a [`HeapBuffer`] with a capacity
-    /// of `text.len() + additional` or `text.len() * 1.5`, whichever is greater
+    /// Callers that want to prevent frequent re-allocations should compute `capacity` with
+    /// [`amortized_growth`]
     #[inline]
-    pub fn with_additional(text: &str, additional: usize) -> Result<Self, ReserveError> {
+    pub fn with_text_and_capacity(text: &str, capacity: usize) -> Result<Self, ReserveError> {
+        debug_assert!(capacity >= text.len());
+
         let len = text.len();
-        let new_capacity = amortized_growth(len, additional);
-        let (cap, ptr) = allocate_ptr(new_capacity)?;
+        let (cap, ptr) = allocate_ptr(capacity)?;
 
         // copy our string into the buffer we just allocated
         //
@@ -111,6 +126,10 @@ impl HeapBuffer {
 
     /// Try to grow the [`HeapBuffer`] by reallocating, returning an error if we fail
     pub fn realloc(&mut self, new_capacity: usize) -> Result<usize, ()> {
+        // We can't create a `Capacity` or `Layout` for this many bytes
+        if new_capacity > MAX_CAPACITY {
+            return Err(());
+        }
         let new_cap = Capacity::new(new_capacity);
 
         // We can't reallocate to a size less than our length, or else we'd clip the string
@@ -230,6 +249,17 @@ impl HeapBuffer {
         self.len = len;
     }
 
+    /// Returns the [`Layout`] of the allocation owned by this [`HeapBuffer`], including the
+    /// capacity if it's stored on the heap
+    #[inline]
+    pub fn layout(&self) -> Layout {
+        if self.cap.is_heap() {
+            heap_capacity::layout(self.capacity())
+        } else {
+            inline_capacity::layout(self.capacity())
+        }
+    }
+
     /// Deallocates the memory owned by the provided [`HeapBuffer`]
     #[inline]
     pub fn dealloc(&mut self) {
@@ -273,6 +303,10 @@ impl Drop for HeapBuffer {
 pub fn allocate_ptr(capacity: usize) -> Result<(Capacity, ptr::NonNull<u8>), ReserveError> {
     // We allocate at least MIN_HEAP_SIZE bytes because we need to allocate at least one byte
     let capacity = capacity.max(MIN_HEAP_SIZE);
+    // Return an error instead of panicking when we'd fail to create a `Capacity` or `Layout`
+    if capacity > MAX_CAPACITY {
+        return Err(ReserveError(()));
+    }
     let cap = Capacity::new(capacity);
 
     // HeapBuffer doesn't support 0 sized allocations, we should always allocate at least
@@ -443,7 +477,9 @@ mod test {
     use test_case::test_case;
 
     use super::{
+        amortized_growth,
         HeapBuffer,
+        MAX_CAPACITY,
         MIN_HEAP_SIZE,
     };
 
@@ -466,6 +502,25 @@ mod test {
         assert_eq!(h.capacity(), core::cmp::max(s.len(), MIN_HEAP_SIZE));
     }
 
+    #[test_case(&[42; 8]; "short")]
+    #[test_case(&[42; 50]; "long")]
+    #[test_case(&[42; EIGHTEEN_MB]; "huge")]
+    fn test_layout(buf: &[u8]) {
+        // we know the buffer is valid UTF-8
+        let s = unsafe { core::str::from_utf8_unchecked(buf) };
+        let h = HeapBuffer::new(s).unwrap();
+        let layout = h.layout();
+
+        if h.cap.is_heap() {
+            // the capacity gets stored on the heap, before the string
+            assert_eq!(layout.size(), h.capacity() + core::mem::size_of::<usize>());
+            assert_eq!(layout.align(), core::mem::align_of::<usize>());
+        } else {
+            assert_eq!(layout.size(), h.capacity());
+            assert_eq!(layout.align(), 1);
+        }
+    }
+
     #[test_case(&[42; 0], 0, Err(MIN_HEAP_SIZE); "empty_empty")]
     #[test_case(&[42; 64], 0, Err(64); "short_empty")]
     #[test_case(&[42; 64], 32, Err(64); "short_to_shorter")]
@@ -553,4 +608,29 @@ mod test {
 
         assert_eq!(h_a.capacity(), h_b.capacity());
     }
+
+    #[test_case(0, 0, 0 ; "empty")]
+    #[test_case(100, 1, 150 ; "amortized")]
+    #[test_case(100, 200, 300 ; "required")]
+    #[test_case(MAX_CAPACITY - 1, 1, MAX_CAPACITY ; "clamped")]
+    #[test_case(MAX_CAPACITY, 1, MAX_CAPACITY + 1 ; "required past max")]
+    #[test_case(usize::MAX - 1, 1, usize::MAX ; "huge")]
+    #[test_case(usize::MAX, usize::MAX, usize::MAX ; "saturates")]
+    fn test_amortized_growth(cur_len: usize, additional: usize, expected: usize) {
+        let new_capacity = amortized_growth(cur_len, additional);
+        assert_eq!(new_capacity, expected);
+        assert!(new_capacity >= cur_len.saturating_add(additional));
+    }
+
+    #[test]
+    fn test_amortized_growth_never_less_than_required() {
+        let lens = [0, 1, 31, 32, 1024, MAX_CAPACITY / 2, MAX_CAPACITY, usize::MAX / 3];
+        let lens = lens.iter().flat_map(|len| [*len, usize::MAX / 2 + len, usize::MAX - len]);
+        for cur_len in lens {
+            for additional in [0, 1, 16, 1024, usize::MAX / 2, usize::MAX] {
+                let required = cur_len.saturating_add(additional);
+                assert!(amortized_growth(cur_len, additional) >= required);
+            }
+        }
+    }
 }
@@ -61,6 +61,11 @@ pub struct Repr(
 );
 static_assertions::assert_eq_size!([u8; MAX_SIZE], Repr);
 
+// SAFETY: A `Repr` uniquely owns its data, which is either stored inline, in a heap allocation
+// that no other `Repr` points to, or is a `&'static str` that is never written to. Cloning a heap
+// allocated `Repr` always copies the data, and mutating requires `&mut self`, so there is no
+// shared mutable state and no interior mutability. The raw pointer is only there to carry
+// provenance, which is why the auto traits need to be implemented manually.
 unsafe impl Send for Repr {}
 unsafe impl Sync for Repr {}
 
@@ -249,15 +254,36 @@ impl Repr {
     /// `additional` bytes this is a no-op
     #[inline]
     pub fn reserve(&mut self, additional: usize) -> Result<(), ReserveError> {
+        self.reserve_with(additional, heap::amortized_growth)
+    }
+
+    /// Reserves the minimum capacity needed to store `additional` more bytes, without amortizing
+    /// growth. If there is already enough capacity to store `additional` bytes this is a no-op
+    #[inline]
+    pub fn reserve_exact(&mut self, additional: usize) -> Result<(), ReserveError> {
+        self.reserve_with(additional, usize::saturating_add)
+    }
+
+    /// Reserves `additional` bytes, using `growth` to compute the new capacity from our current
+    /// length and `additional`, if we need to (re)allocate on the heap
+    #[inline(always)]
+    fn reserve_with(
+        &mut self,
+        additional: usize,
+        growth: fn(usize, usize) -> usize,
+    ) -> Result<(), ReserveError> {
         let len = self.len();
-        let needed_capacity = len.checked_add(additional).ok_or(ReserveError(()))?;
 
-        if !self.is_static_str() && needed_capacity <= self.capacity() {
-            // we already have enough space, no-op
-            // If self.is_static_str() is true, then we would have to convert
-            // it to other variants since static_str variant cannot be modified.
-            Ok(())
-        } else if needed_capacity <= MAX_SIZE {
+        // Fast path for the common case, e.g. pushing a char, where we already have enough space.
+        // `len <= capacity` always holds, so unlike adding to `len` this can't overflow.
+        // If self.is_static_str() is true, then we would have to convert it to other variants
+        // since static_str variant cannot be modified.
+        if !self.is_static_str() && additional <= self.capacity() - len {
+            return Ok(());
+        }
+
+        let needed_capacity = len.checked_add(additional).ok_or(ReserveError(()))?;
+        if needed_capacity <= MAX_SIZE {
             // It's possible to have a `Repr` that is heap allocated with a capacity less than
             // MAX_SIZE, if that `Repr` was created From a String or Box<str>
             //
@@ -267,7 +293,8 @@ impl Repr {
             Ok(())
         } else if !self.is_heap_allocated() {
             // We're not heap allocated, but need to be, create a HeapBuffer
-            let heap = HeapBuffer::with_additional(self.as_str(), additional)?;
+            let new_capacity = growth(len, additional);
+            let heap = HeapBuffer::with_text_and_capacity(self.as_str(), new_capacity)?;
             *self = Repr::from_heap(heap);
             Ok(())
         } else {
@@ -276,12 +303,12 @@ impl Repr {
             // SAFETY: We checked above to see if we're heap allocated
             let heap_buffer = unsafe { self.as_mut_heap() };
 
-            // To reduce allocations, we amortize our growth
-            let amortized_capacity = heap::amortized_growth(len, additional);
+            // To reduce allocations, `reserve` amortizes our growth, `reserve_exact` does not
+            let new_capacity = growth(len, additional);
             // Attempt to grow our capacity, allocating a new HeapBuffer on failure
-            if heap_buffer.realloc(amortized_capacity).is_err() {
+            if heap_buffer.realloc(new_capacity).is_err() {
                 // Create a new HeapBuffer
-                let heap = HeapBuffer::with_additional(self.as_str(), additional)?;
+                let heap = HeapBuffer::with_text_and_capacity(self.as_str(), new_capacity)?;
                 *self = Repr::from_heap(heap);
             }
 
@@ -404,10 +431,34 @@ impl Repr {
         unsafe { core::slice::from_raw_parts(pointer, length) }
     }
 
+    /// Returns the string content, like [`Repr::as_str`], but can be evaluated at compile time.
+    ///
+    /// Note: Unlike [`Repr::as_slice`] this branches on our variant, which is why we don't use it
+    /// at runtime.
+    #[inline]
+    #[rustversion::attr(since(1.64), const)]
+    pub fn const_as_str(&self) -> &str {
+        let last_byte = self.last_byte();
+        let (pointer, length) = if last_byte >= HEAP_MASK {
+            (self.0 as *const u8, self.1)
+        } else {
+            let length = last_byte.wrapping_sub(LENGTH_MASK) as usize;
+            let length = if length < MAX_SIZE { length } else { MAX_SIZE };
+            (self as *const Self as *const u8, length)
+        };
+
+        // SAFETY: We know the data is valid, aligned, part of the same contiguous allocated chunk,
+        // and valid for the lifetime of self. A `Repr` contains valid UTF-8
+        unsafe { core::str::from_utf8_unchecked(core::slice::from_raw_parts(pointer, length)) }
+    }
+
     #[inline]
     pub fn as_str(&self) -> &str {
+        let slice = self.as_slice();
+        // Catch any unsafe code that broke our invariant, before we hand out an invalid `&str`
+        debug_assert!(core::str::from_utf8(slice).is_ok(), "Repr contains invalid UTF-8");
         // SAFETY: A `Repr` contains valid UTF-8
-        unsafe { core::str::from_utf8_unchecked(self.as_slice()) }
+        unsafe { core::str::from_utf8_unchecked(slice) }
     }
 
     /// Returns the length of the string that we're storing
@@ -471,12 +522,41 @@ impl Repr {
         }
     }
 
+    /// Returns the capacity a [`Repr`] created from a string of `len` bytes would have
+    #[inline]
+    pub const fn capacity_for(len: usize) -> usize {
+        if len <= MAX_SIZE {
+            MAX_SIZE
+        } else if len < heap::MIN_HEAP_SIZE {
+            // `HeapBuffer` never allocates less than `MIN_HEAP_SIZE` bytes
+            heap::MIN_HEAP_SIZE
+        } else {
+            len
+        }
+    }
+
     #[inline(always)]
     pub fn is_heap_allocated(&self) -> bool {
         let last_byte = self.last_byte();
         last_byte == HEAP_MASK
     }
 
+    #[inline]
+    pub fn heap_size(&self) -> usize {
+        #[cold]
+        fn heap_size(this: &Repr) -> usize {
+            // SAFETY: We just checked the discriminant to make sure we're heap allocated
+            let heap_buffer = unsafe { this.as_heap() };
+            heap_buffer.layout().size()
+        }
+
+        if self.is_heap_allocated() {
+            heap_size(self)
+        } else {
+            0
+        }
+    }
+
     #[inline(always)]
     const fn is_static_str(&self) -> bool {
         let last_byte = self.last_byte();
@@ -720,6 +800,17 @@ impl Clone for Repr {
     }
 }
 
+/// Two [`Repr`]s are equal if they contain the same string, regardless of whether they're stored
+/// inline, on the heap, or as a `&'static str`.
+impl PartialEq for Repr {
+    #[inline]
+    fn eq(&self, other: &Self) -> bool {
+        self.as_str() == other.as_str()
+    }
+}
+
+impl Eq for Repr {}
+
 impl Drop for Repr {
     #[inline]
     fn drop(&mut self) {
@@ -781,7 +872,20 @@ impl<'a> Extend<Cow<'a, str>> for Repr {
 
 impl Extend<String> for Repr {
     fn extend<T: IntoIterator<Item = String>>(&mut self, iter: T) {
-        iter.into_iter().for_each(move |s| self.push_str(&s));
+        let mut iter = iter.into_iter();
+
+        if self.is_empty() {
+            match iter.next() {
+                // We'd need to allocate a new buffer anyway, so adopt the one from the `String`
+                Some(s) if s.len() > self.capacity() => {
+                    *self = Repr::from_string(s, true).unwrap_with_msg();
+                }
+                Some(s) => self.push_str(&s),
+                None => return,
+            }
+        }
+
+        iter.for_each(move |s| self.push_str(&s));
     }
 }
 
@@ -864,6 +968,7 @@ mod tests {
 
     #[test_case(""; "empty")]
     #[test_case("abc"; "short")]
+    #[test_case("abcdefghijklmnopqrstuvwx"; "max size")]
     #[test_case("hello world! I am a longer string 🦀"; "long")]
     fn test_from_utf8_valid(s: &'static str) {
         let bytes = s.as_bytes();
@@ -871,6 +976,19 @@ mod tests {
 
         assert_eq!(r.as_str(), s);
         assert_eq!(r.len(), s.len());
+        // short inputs get inlined, without an intermediate `String`
+        assert_eq!(r.is_heap_allocated(), s.len() > MAX_SIZE);
+    }
+
+    #[test_case(&[0xFF]; "invalid byte")]
+    #[test_case(&[b'a', b'b', 0xC0, 0x80]; "overlong encoding")]
+    #[test_case(&[0xF0, 0x9F, 0xA6]; "truncated char")]
+    #[test_case(&[0xED, 0xA0, 0x80]; "surrogate")]
+    fn test_from_utf8_invalid(bytes: &[u8]) {
+        let expected = core::str::from_utf8(bytes).unwrap_err();
+        let err = Repr::from_utf8(bytes).err().unwrap();
+
+        assert_eq!(err, expected);
     }
 
     #[quickcheck]
@@ -12,6 +12,7 @@ use core::{
 use super::traits::IntoRepr;
 use super::Repr;
 use crate::{
+    ReserveError,
     ToCompactStringError,
     UnwrapWithMsg,
 };
@@ -145,6 +146,53 @@ impl IntoRepr for i128 {
     }
 }
 
+impl Repr {
+    /// Create a [`Repr`] from an `i128`, with `sep` inserted between every group of three digits,
+    /// e.g. `-1,234,567`
+    #[inline]
+    pub fn from_int_grouped(value: i128, sep: char) -> Result<Self, ReserveError> {
+        Self::from_digits_grouped(value < 0, value.unsigned_abs(), sep)
+    }
+
+    /// Create a [`Repr`] from a `u128`, with `sep` inserted between every group of three digits,
+    /// e.g. `1,234,567`
+    #[inline]
+    pub fn from_uint_grouped(value: u128, sep: char) -> Result<Self, ReserveError> {
+        Self::from_digits_grouped(false, value, sep)
+    }
+
+    fn from_digits_grouped(
+        is_negative: bool,
+        value: u128,
+        sep: char,
+    ) -> Result<Self, ReserveError> {
+        let mut buffer = itoa::Buffer::new();
+        let digits = buffer.format(value);
+
+        let mut sep_buf = [0; 4];
+        let sep = sep.encode_utf8(&mut sep_buf);
+
+        // the leading group has between one and three digits, every other group has exactly three
+        let num_seps = (digits.len() - 1) / 3;
+        let leading = digits.len() - num_seps * 3;
+
+        // allocate exactly once, the result is stored inline if it fits
+        let len = is_negative as usize + digits.len() + num_seps * sep.len();
+        let mut repr = Repr::with_capacity(len)?;
+
+        if is_negative {
+            repr.push_str("-");
+        }
+        repr.push_str(&digits[..leading]);
+        for start in (leading..digits.len()).step_by(3) {
+            repr.push_str(sep);
+            repr.push_str(&digits[start..start + 3]);
+        }
+
+        Ok(repr)
+    }
+}
+
 /// Defines the implementation of [`IntoRepr`] for NonZero integer types
 macro_rules! impl_NonZero_IntoRepr {
     ($t:path) => {
@@ -423,7 +471,13 @@ impl NumChars for isize {
 mod tests {
     use alloc::string::ToString;
 
+    use test_case::test_case;
+
     use super::IntoRepr;
+    use crate::repr::{
+        Repr,
+        MAX_SIZE,
+    };
 
     #[test]
     fn test_from_u8_sanity() {
@@ -558,4 +612,72 @@ mod tests {
             assert_eq!(repr.as_str(), x.to_string());
         }
     }
+
+    #[test_case(0, ',', "0"; "zero")]
+    #[test_case(7, ',', "7"; "one digit")]
+    #[test_case(999, ',', "999"; "under a thousand")]
+    #[test_case(1000, ',', "1,000"; "thousand")]
+    #[test_case(1234567, ',', "1,234,567"; "million")]
+    #[test_case(-999, ',', "-999"; "negative under a thousand")]
+    #[test_case(-1234567, ',', "-1,234,567"; "negative")]
+    #[test_case(1234567, '_', "1_234_567"; "underscore")]
+    #[test_case(-1234567, '\u{202F}', "-1\u{202F}234\u{202F}567"; "multi byte separator")]
+    #[test_case(i128::MIN, ',', "-170,141,183,460,469,231,731,687,303,715,884,105,728"; "min")]
+    fn test_from_int_grouped(val: i128, sep: char, expected: &str) {
+        let repr = Repr::from_int_grouped(val, sep).unwrap();
+        assert_eq!(repr.as_str(), expected);
+        assert_eq!(repr.is_heap_allocated(), expected.len() > MAX_SIZE);
+    }
+
+    #[test_case(0, ',', "0"; "zero")]
+    #[test_case(100, ',', "100"; "under a thousand")]
+    #[test_case(1000000, ' ', "1 000 000"; "space")]
+    #[test_case(u128::MAX, '_', "340_282_366_920_938_463_463_374_607_431_768_211_455"; "max")]
+    fn test_from_uint_grouped(val: u128, sep: char, expected: &str) {
+        let repr = Repr::from_uint_grouped(val, sep).unwrap();
+        assert_eq!(repr.as_str(), expected);
+    }
+
+    #[test]
+    fn test_from_int_grouped_matches_to_string() {
+        let vals = [i128::MIN, -1_000_001, -1000, -1, 0, 1, 999, 1000, 123_456_789, i128::MAX];
+
+        for x in &vals {
+            let repr = Repr::from_int_grouped(*x, '_').unwrap();
+            assert_eq!(repr.as_str().replace('_', ""), x.to_string());
+        }
+    }
+
+    #[test]
+    fn test_into_repr_eq() {
+        let vals = [0, 1, 42, 1_000_000, u64::MAX - 1, u64::MAX];
+
+        for x in &vals {
+            // equal numbers produce equal reprs
+            assert!(u64::into_repr(*x).unwrap() == u64::into_repr(*x).unwrap());
+            assert!(i128::into_repr(*x as i128).unwrap() == u64::into_repr(*x).unwrap());
+
+            // different numbers produce different reprs
+            for y in vals.iter().filter(|y| *y != x) {
+                assert!(u64::into_repr(*x).unwrap() != u64::into_repr(*y).unwrap());
+            }
+        }
+
+        // the same value formatted from a different type
+        assert!(i8::into_repr(-1).unwrap() == i64::into_repr(-1).unwrap());
+        assert!(i8::into_repr(-1).unwrap() != u8::into_repr(1).unwrap());
+    }
+
+    #[test]
+    fn test_into_repr_eq_across_variants() {
+        // a number with 39 digits is heap allocated
+        let heap = u128::into_repr(u128::MAX).unwrap();
+        assert!(heap.is_heap_allocated());
+        assert!(heap == Repr::new(&u128::MAX.to_string()).unwrap());
+
+        let inline = u64::into_repr(12345).unwrap();
+        assert!(!inline.is_heap_allocated());
+        assert!(inline == Repr::from_static_str("12345"));
+        assert!(inline != heap);
+    }
 }
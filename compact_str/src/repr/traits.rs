@@ -1,5 +1,10 @@
+use core::fmt;
+
 use super::Repr;
-use crate::ToCompactStringError;
+use crate::{
+    ReserveError,
+    ToCompactStringError,
+};
 
 const FALSE: Repr = Repr::new_inline("false");
 const TRUE: Repr = Repr::new_inline("true");
@@ -27,6 +32,42 @@ impl IntoRepr for f64 {
     }
 }
 
+impl Repr {
+    /// Create a [`Repr`] from an `f32`, formatted with exactly `precision` digits after the
+    /// decimal point, i.e. the same as `format!("{:.precision$}", value)`
+    #[inline]
+    pub fn from_f32_precision(value: f32, precision: usize) -> Result<Self, ReserveError> {
+        Self::from_fmt(format_args!("{:.*}", precision, value))
+    }
+
+    /// Create a [`Repr`] from an `f64`, formatted with exactly `precision` digits after the
+    /// decimal point, i.e. the same as `format!("{:.precision$}", value)`
+    #[inline]
+    pub fn from_f64_precision(value: f64, precision: usize) -> Result<Self, ReserveError> {
+        Self::from_fmt(format_args!("{:.*}", precision, value))
+    }
+
+    /// Writes the formatted `args` into a new [`Repr`]. The output is written inline, and we only
+    /// heap allocate if it's longer than `MAX_SIZE`
+    fn from_fmt(args: fmt::Arguments<'_>) -> Result<Self, ReserveError> {
+        struct Writer(Repr);
+
+        impl fmt::Write for Writer {
+            #[inline]
+            fn write_str(&mut self, s: &str) -> fmt::Result {
+                self.0.reserve(s.len()).map_err(|_| fmt::Error)?;
+                self.0.push_str(s);
+                Ok(())
+            }
+        }
+
+        let mut writer = Writer(Repr::new_inline(""));
+        // Formatting a float never fails, so the only possible error is from allocating
+        fmt::write(&mut writer, args).map_err(|_| ReserveError(()))?;
+        Ok(writer.0)
+    }
+}
+
 impl IntoRepr for bool {
     #[inline]
     fn into_repr(self) -> Result<Repr, ToCompactStringError> {
@@ -51,8 +92,12 @@ mod tests {
     use alloc::string::ToString;
 
     use quickcheck_macros::quickcheck;
+    use test_case::test_case;
 
-    use super::IntoRepr;
+    use super::{
+        IntoRepr,
+        Repr,
+    };
 
     #[test]
     fn test_into_repr_bool() {
@@ -154,4 +199,47 @@ mod tests {
             assert_eq!(val, roundtrip);
         }
     }
+
+    #[test_case(0.005, 2; "round half")]
+    #[test_case(0.015, 2; "round half up")]
+    #[test_case(2.5, 0; "zero precision")]
+    #[test_case(-1.23456, 3; "negative")]
+    #[test_case(1.0, 6; "trailing zeros")]
+    #[test_case(123456789.9876, 4; "large")]
+    #[test_case(f64::MAX, 2; "heap")]
+    #[test_case(f64::NAN, 2; "nan")]
+    #[test_case(f64::NEG_INFINITY, 2; "neg infinity")]
+    fn test_from_f64_precision(val: f64, precision: usize) {
+        let repr = Repr::from_f64_precision(val, precision).unwrap();
+        assert_eq!(repr.as_str(), format!("{:.*}", precision, val));
+    }
+
+    #[test]
+    fn test_from_f64_precision_inlines() {
+        let repr = Repr::from_f64_precision(9.87654, 2).unwrap();
+        assert_eq!(repr.as_str(), "9.88");
+        assert!(!repr.is_heap_allocated());
+
+        let repr = Repr::from_f64_precision(9.87654, 40).unwrap();
+        assert_eq!(repr.as_str(), format!("{:.40}", 9.87654));
+        assert!(repr.is_heap_allocated());
+    }
+
+    #[test_case(0.005, 2; "round half")]
+    #[test_case(2.5, 0; "zero precision")]
+    #[test_case(-1.23456, 3; "negative")]
+    #[test_case(f32::MAX, 2; "heap")]
+    #[test_case(f32::NAN, 2; "nan")]
+    fn test_from_f32_precision(val: f32, precision: usize) {
+        let repr = Repr::from_f32_precision(val, precision).unwrap();
+        assert_eq!(repr.as_str(), format!("{:.*}", precision, val));
+    }
+
+    #[quickcheck]
+    #[cfg_attr(miri, ignore)]
+    fn quickcheck_from_f64_precision(val: f64, precision: u8) {
+        let precision = precision as usize;
+        let repr = Repr::from_f64_precision(val, precision).unwrap();
+        assert_eq!(repr.as_str(), format!("{:.*}", precision, val));
+    }
 }
@@ -238,6 +238,13 @@ fn proptest_from_utf16_roundtrips(#[strategy(rand_unicode())] control: String) {
     assert_eq!(compact, control);
 }
 
+#[proptest]
+#[cfg_attr(miri, ignore)]
+fn proptest_len_utf16(#[strategy(rand_unicode())] control: String) {
+    let compact = CompactString::new(&control);
+    prop_assert_eq!(compact.len_utf16(), control.encode_utf16().count());
+}
+
 #[proptest]
 #[cfg_attr(miri, ignore)]
 fn proptest_from_utf16_random(#[strategy(rand_u16s())] buf: Vec<u16>) {
@@ -378,6 +385,54 @@ fn proptest_to_lowercase(#[strategy(rand_unicode())] control: String) {
     prop_assert_eq!(control, compact);
 }
 
+#[proptest]
+#[cfg_attr(miri, ignore)]
+fn proptest_to_case_cow(#[strategy(rand_unicode())] control: String) {
+    let compact = CompactString::new(&control);
+
+    // we only borrow if converting wouldn't change anything
+    let lower = compact.to_lowercase_cow();
+    prop_assert_eq!(&lower, &control.to_lowercase());
+    prop_assert_eq!(matches!(lower, Cow::Borrowed(_)), control.to_lowercase() == control);
+
+    let upper = compact.to_uppercase_cow();
+    prop_assert_eq!(&upper, &control.to_uppercase());
+    prop_assert_eq!(matches!(upper, Cow::Borrowed(_)), control.to_uppercase() == control);
+}
+
+#[test_case("hello, wörld 🦀", true, false ; "lowercase")]
+#[test_case("HELLO, WÖRLD 🦀", false, true ; "uppercase")]
+#[test_case("Hello, Wörld 🦀", false, false ; "mixed case")]
+#[test_case("农历新年 123", true, true ; "no case")]
+#[test_case("", true, true ; "empty")]
+#[test_case("ὈΔΥΣΣΕΎΣ", false, true ; "final sigma")]
+#[test_case("tschüß", true, false ; "expands")]
+fn test_to_case_cow(text: &'static str, is_lowercase: bool, is_uppercase: bool) {
+    let compact = CompactString::new(text);
+
+    match compact.to_lowercase_cow() {
+        Cow::Borrowed(lower) => {
+            assert!(is_lowercase);
+            assert_eq!(lower.as_ptr(), compact.as_ptr());
+        }
+        Cow::Owned(lower) => {
+            assert!(!is_lowercase);
+            assert_eq!(lower, text.to_lowercase());
+        }
+    }
+
+    match compact.to_uppercase_cow() {
+        Cow::Borrowed(upper) => {
+            assert!(is_uppercase);
+            assert_eq!(upper.as_ptr(), compact.as_ptr());
+        }
+        Cow::Owned(upper) => {
+            assert!(!is_uppercase);
+            assert_eq!(upper, text.to_uppercase());
+        }
+    }
+}
+
 #[test]
 fn test_const_creation() {
     const EMPTY: CompactString = CompactString::new_inline("");
@@ -546,12 +601,101 @@ fn test_extend_from_empty_strs() {
     assert!(!compact.is_heap_allocated());
 }
 
+#[test]
+fn test_extend_empty_adopts_first_buffer() {
+    let large = String::from("a large string, that was built somewhere else");
+    let ptr = large.as_ptr();
+
+    let mut compact = CompactString::default();
+    compact.extend(core::iter::once(large));
+    assert_eq!(compact, "a large string, that was built somewhere else");
+    // the allocation from the `String` was re-used
+    assert!(compact.is_heap_allocated());
+    assert_eq!(compact.as_ptr(), ptr);
+
+    let large = CompactString::from("another large string, built somewhere else");
+    let ptr = large.as_ptr();
+
+    let mut compact = CompactString::default();
+    compact.extend(core::iter::once(large));
+    assert_eq!(compact, "another large string, built somewhere else");
+    assert_eq!(compact.as_ptr(), ptr);
+
+    // any following strings get appended
+    let mut compact = CompactString::default();
+    compact.extend([String::from("a large string, that was built somewhere else"), "!".into()]);
+    assert_eq!(compact, "a large string, that was built somewhere else!");
+}
+
+#[test]
+fn test_extend_does_not_adopt_when_not_needed() {
+    // short strings are still copied inline
+    let mut compact = CompactString::default();
+    compact.extend([String::from("short"), String::from(" and sweet")]);
+    assert_eq!(compact, "short and sweet");
+    assert!(!compact.is_heap_allocated());
+
+    // if we already have enough capacity, we keep our own buffer
+    let mut compact = CompactString::with_capacity(256);
+    let ptr = compact.as_ptr();
+    compact.extend(core::iter::once(CompactString::from("a large string, that fits in our buffer")));
+    assert_eq!(compact, "a large string, that fits in our buffer");
+    assert_eq!(compact.as_ptr(), ptr);
+    assert!(compact.capacity() >= 256);
+
+    // and if we're not empty, we append as usual
+    let mut compact = CompactString::from("prefix: ");
+    compact.extend(core::iter::once(String::from("a large string, that was built somewhere else")));
+    assert_eq!(compact, "prefix: a large string, that was built somewhere else");
+}
+
 #[test]
 fn test_compact_str_is_send_and_sync() {
     fn is_send_and_sync<T: Send + Sync>() {}
     is_send_and_sync::<CompactString>();
 }
 
+#[test]
+#[cfg_attr(miri, ignore)]
+fn test_clone_and_drop_across_threads() {
+    use std::sync::Arc;
+    use std::thread;
+
+    const TEXT: &str = "i am a long string that will be allocated on the heap";
+
+    let shared = Arc::new(CompactString::new(TEXT));
+    let handles: Vec<_> = (0..4)
+        .map(|i| {
+            let shared = Arc::clone(&shared);
+            thread::spawn(move || {
+                for _ in 0..100 {
+                    // read the shared string, while pushing to our own clone
+                    let mut clone = CompactString::clone(&shared);
+                    assert_eq!(shared.as_str(), TEXT);
+
+                    clone.push_str(" and then some");
+                    clone.push(char::from(b'0' + i));
+                    assert!(clone.starts_with(TEXT));
+                    drop(clone);
+                }
+            })
+        })
+        .collect();
+
+    // moving an owned string to another thread, and dropping it there
+    let owned = CompactString::new(TEXT);
+    let handle = thread::spawn(move || {
+        assert_eq!(owned, TEXT);
+        drop(owned);
+    });
+
+    for handle in handles {
+        handle.join().unwrap();
+    }
+    handle.join().unwrap();
+    assert_eq!(shared.as_str(), TEXT);
+}
+
 #[test_case(CompactString::default(); "inline")]
 #[test_case(CompactString::from_static_str(""); "static_str")]
 fn test_fmt_write(mut compact: CompactString) {
@@ -647,6 +791,60 @@ fn test_plus_equals_operator_static_str() {
     assert_eq!(m, "ab");
 }
 
+#[test]
+fn test_push_static_str_copies_once() {
+    const TEXT: &str = "a &'static str that is shared by all of its clones";
+    let original = CompactString::from_static_str(TEXT);
+    let mut pushed = original.clone();
+
+    // the first push copies the `&'static str` directly into an amortized heap buffer, instead
+    // of first copying it into an exactly sized buffer and then growing that
+    pushed.push('!');
+    assert!(pushed.is_heap_allocated());
+    assert_eq!(pushed.capacity(), TEXT.len() * 3 / 2);
+
+    // which leaves enough room for the next pushes, so they don't copy again
+    let ptr = pushed.as_ptr();
+    pushed.push_str("??");
+    pushed.push('!');
+    assert_eq!(pushed.as_ptr(), ptr);
+    assert_eq!(pushed, "a &'static str that is shared by all of its clones!??!");
+
+    // and the shared `&'static str` is never touched
+    assert_eq!(original.as_static_str(), Some(TEXT));
+}
+
+#[allow(clippy::op_ref)]
+#[test]
+fn test_plus_equals_operator_coercions() {
+    // Implementing more than one `AddAssign<T>` for CompactString would break appending &String or
+    // other types that deref to str, so like String we only implement `AddAssign<&str>`. Below we
+    // assert all of these still compile
+
+    // += &CompactString, long enough to force a reallocation
+    let mut m = CompactString::from("a");
+    let long = CompactString::from("this string is long enough to be heap allocated");
+    m += &long;
+    assert_eq!(m, "athis string is long enough to be heap allocated");
+    assert!(m.is_heap_allocated());
+
+    // += &String
+    let mut m = CompactString::from("a");
+    m += &String::from("b");
+    assert_eq!(m, "ab");
+    // += &Box<str>
+    let box_str = String::from("c").into_boxed_str();
+    m += &box_str;
+    assert_eq!(m, "abc");
+    // += &Cow<'a, str>
+    let cow = Cow::from("d");
+    m += &cow;
+    assert_eq!(m, "abcd");
+    // += a multi-byte char, via `encode_utf8`
+    m += '🦀'.encode_utf8(&mut [0; 4]);
+    assert_eq!(m, "abcd🦀");
+}
+
 #[test]
 fn test_u8_to_compact_string() {
     let vals = [u8::MIN, 1, 42, u8::MAX - 2, u8::MAX - 1, u8::MAX];
@@ -1231,6 +1429,50 @@ fn test_into_string_empty_static_str() {
     assert_eq!(str_len, new_str_cap);
 }
 
+#[test]
+fn test_from_static_str_long_does_not_allocate() {
+    const TEXT: &str = "a long &'static str that is too long to be stored inline";
+    const COMPACT: CompactString = CompactString::from_static_str(TEXT);
+
+    let compact = COMPACT;
+    // we point at the original `&'static str`
+    assert_eq!(compact, TEXT);
+    assert_eq!(compact.as_ptr(), TEXT.as_ptr());
+    assert_eq!(compact.as_static_str(), Some(TEXT));
+    assert!(!compact.is_heap_allocated());
+    assert_eq!(compact.heap_size(), 0);
+    assert_eq!(compact.capacity(), TEXT.len());
+
+    // cloning copies the pointer and length, there's no reference count
+    let clone = compact.clone();
+    assert_eq!(clone, TEXT);
+    assert_eq!(clone.as_ptr(), TEXT.as_ptr());
+    assert_eq!(clone.as_static_str(), Some(TEXT));
+    assert!(!clone.is_heap_allocated());
+
+    let mut clone_into = CompactString::new("a heap allocated string that gets replaced");
+    clone_into.clone_from(&compact);
+    assert_eq!(clone_into.as_ptr(), TEXT.as_ptr());
+    assert!(!clone_into.is_heap_allocated());
+
+    // the original is unaffected by dropping the clones
+    drop(clone);
+    drop(clone_into);
+    assert_eq!(compact, TEXT);
+}
+
+#[test_case("" ; "empty")]
+#[test_case("hello world" ; "inline")]
+#[test_case("this is a long string that will be heap allocated" ; "heap")]
+#[test_case("ありがとうございます, and some ascii" ; "multi byte")]
+fn test_to_string(text: &'static str) {
+    // the number of allocations this makes is checked in `tests/allocations.rs`
+    for compact in [CompactString::new(text), CompactString::from_static_str(text)] {
+        let string = compact.to_string();
+        assert_eq!(string, text);
+    }
+}
+
 #[test]
 fn test_truncate_noops_if_new_len_greater_than_current() {
     let mut short = CompactString::from("short");
@@ -1265,7 +1507,7 @@ fn test_truncate_noops_if_new_len_greater_than_current_static_str() {
 }
 
 #[test]
-#[should_panic(expected = "new_len must lie on char boundary")]
+#[should_panic(expected = "byte index 1 is not a char boundary")]
 fn test_truncate_panics_on_non_char_boundary() {
     let mut emojis = CompactString::from("😀😀😀😀");
     assert!('😀'.len_utf8() > 1);
@@ -1352,6 +1594,67 @@ fn test_insert(to_compact: fn(&'static str) -> CompactString) {
     );
 }
 
+#[test_case("", 0, '🦀', "🦀" ; "empty")]
+#[test_case("ü€", 0, '🦀', "🦀ü€" ; "start")]
+#[test_case("ü€", 1, '🦀', "ü🦀€" ; "between multibyte")]
+#[test_case("ü€", 2, '🦀', "ü€🦀" ; "end")]
+#[test_case("hello world", 5, ',', "hello, world" ; "ascii")]
+fn test_insert_at_char(initial: &'static str, char_idx: usize, ch: char, expected: &str) {
+    let mut compact = CompactString::new(initial);
+    compact.insert_at_char(char_idx, ch);
+    assert_eq!(compact, expected);
+
+    let mut compact = CompactString::from_static_str(initial);
+    compact.insert_at_char(char_idx, ch);
+    assert_eq!(compact, expected);
+}
+
+#[test]
+#[should_panic(expected = "char index 3 out of bounds, string has 2 characters")]
+fn test_insert_at_char_out_of_bounds() {
+    let mut compact = CompactString::new("ü€");
+    compact.insert_at_char(3, '🦀');
+}
+
+#[test_case("hello world", 6, "worldhello " ; "ascii")]
+#[test_case("ü€🦀", 2, "€🦀ü" ; "multi byte")]
+#[test_case("ü€🦀", 5, "🦀ü€" ; "multi byte end")]
+#[test_case("hello world", 0, "hello world" ; "zero")]
+#[test_case("hello world", 11, "hello world" ; "len")]
+#[test_case("", 0, "" ; "empty")]
+#[test_case("a string that is long enough to be heap allocated", 2, "string that is long enough to be heap allocateda " ; "heap")]
+fn test_rotate(text: &'static str, mid: usize, expected: &str) {
+    for mut compact in [CompactString::new(text), CompactString::from_static_str(text)] {
+        compact.rotate_left(mid);
+        assert_eq!(compact, expected);
+
+        // rotating right by the same amount undoes it
+        compact.rotate_right(mid);
+        assert_eq!(compact, text);
+    }
+}
+
+#[test]
+#[should_panic(expected = "byte index 1 is not a char boundary")]
+fn test_rotate_left_not_char_boundary() {
+    let mut compact = CompactString::new("ü€🦀");
+    compact.rotate_left(1);
+}
+
+#[test]
+#[should_panic(expected = "byte index 8 is not a char boundary")]
+fn test_rotate_right_not_char_boundary() {
+    let mut compact = CompactString::new("ü€🦀");
+    compact.rotate_right(1);
+}
+
+#[test]
+#[should_panic(expected = "byte index 10 is out of bounds")]
+fn test_rotate_right_out_of_bounds() {
+    let mut compact = CompactString::new("ü€🦀");
+    compact.rotate_right(10);
+}
+
 #[test]
 fn test_remove() {
     let mut control = String::from("🦄🦀hello🎶world🇺🇸");
@@ -1392,6 +1695,36 @@ fn test_remove_str_len() {
     compact.remove(compact.len());
 }
 
+#[test_case("abcdefg", 2, 'c', "abgdef"; "ascii middle")]
+#[test_case("abcdefg", 0, 'a', "gbcdef"; "ascii first")]
+#[test_case("abcdefg", 6, 'g', "abcdef"; "ascii last")]
+#[test_case("a", 0, 'a', ""; "only char")]
+#[test_case("ab🦀cdé", 2, '🦀', "abécd"; "shorter replacement")]
+#[test_case("abcd🦀", 1, 'b', "a🦀cd"; "longer replacement")]
+#[test_case("🎶 a string on the heap 🦀", 0, '🎶', "🦀 a string on the heap "; "same length")]
+#[test_case("ü on the heap, past inline 🦀", 0, 'ü', "🦀 on the heap, past inline "; "heap")]
+fn test_swap_remove(text: &'static str, idx: usize, removed: char, expected: &str) {
+    for mut compact in [CompactString::new(text), CompactString::from_static_str(text)] {
+        assert_eq!(compact.swap_remove(idx), removed);
+        assert_eq!(compact, expected);
+        assert_eq!(compact.len(), text.len() - removed.len_utf8());
+    }
+}
+
+#[test]
+#[should_panic(expected = "cannot remove a char from the end of a string")]
+fn test_swap_remove_str_len() {
+    let mut compact = CompactString::new("hello world");
+    compact.swap_remove(compact.len());
+}
+
+#[test]
+#[should_panic(expected = "byte index 1 is not a char boundary")]
+fn test_swap_remove_not_char_boundary() {
+    let mut compact = CompactString::new("🦀 crab");
+    compact.swap_remove(1);
+}
+
 #[test]
 fn test_with_capacity_16711422() {
     // Fuzzing with AFL on a 32-bit ARM arch found this bug!
@@ -1424,6 +1757,25 @@ fn test_with_capacity_16711422() {
     assert_eq!(compact, std_str);
 }
 
+#[test_case("hello world", 11 ; "ascii")]
+#[test_case("grüße, 农历新年", 11 ; "multi byte bmp")]
+#[test_case("crab 🦀", 7 ; "astral")]
+#[test_case("🦀🦀🦀🦀🦀🦀🦀🦀", 16 ; "astral heap")]
+#[test_case("", 0 ; "empty")]
+fn test_len_utf16(text: &'static str, expected: usize) {
+    for compact in [CompactString::new(text), CompactString::from_static_str(text)] {
+        assert_eq!(compact.len_utf16(), expected);
+        assert_eq!(compact.len_utf16(), text.encode_utf16().count());
+    }
+
+    // ascii is one code unit per byte, the rest of the BMP is one code unit per char
+    if text.is_ascii() {
+        assert_eq!(expected, text.len());
+    } else if text.chars().all(|c| c.len_utf16() == 1) {
+        assert_eq!(expected, text.chars().count());
+    }
+}
+
 #[test]
 fn test_from_utf16() {
     let control = String::from("🦄 hello world! 🎮 ");
@@ -1443,6 +1795,30 @@ fn test_from_utf16() {
     }
 }
 
+#[test]
+fn test_from_utf16_with_bom() {
+    let control = "🦄 hello world! 🎮 ";
+    let utf16_buf: Vec<u16> = control.encode_utf16().collect();
+    let mut with_bom = vec![0xFEFF];
+    with_bom.extend_from_slice(&utf16_buf);
+
+    // with a BOM
+    assert_eq!(CompactString::from_utf16_with_bom(&with_bom).unwrap(), control);
+    // without a BOM
+    assert_eq!(CompactString::from_utf16_with_bom(&utf16_buf).unwrap(), control);
+    // only a BOM
+    let compact = CompactString::from_utf16_with_bom([0xFEFF]).unwrap();
+    assert_eq!(compact, "");
+    // only a single BOM is stripped
+    assert_eq!(CompactString::from_utf16_with_bom([0xFEFF, 0xFEFF]).unwrap(), "\u{FEFF}");
+    // an empty slice
+    assert_eq!(CompactString::from_utf16_with_bom([]).unwrap(), "");
+
+    // an unpaired surrogate after the BOM
+    assert!(CompactString::from_utf16_with_bom([0xFEFF, 0x0068, 0xD800, 0x0069]).is_err());
+    assert!(CompactString::from_utf16_with_bom([0xFEFF, 0xDD1E]).is_err());
+}
+
 #[test]
 fn test_reserve_shrink_roundtrip() {
     const TEXT: &str = "Hello.";
@@ -1580,6 +1956,429 @@ fn test_reserve_shrink_roundtrip_static_inline() {
     assert_eq!(s, TEXT);
 }
 
+#[test_case(CompactString::from, "Hello." ; "inline")]
+#[test_case(CompactString::from, "Hello, world! How are you today?" ; "heap")]
+#[test_case(CompactString::from_static_str, "Hello." ; "static_str inline")]
+#[test_case(CompactString::from_static_str, "Hello, world! How are you today?" ; "static_str")]
+fn test_reserve_capacity_survives_mutation(
+    to_compact: fn(&'static str) -> CompactString,
+    text: &'static str,
+) {
+    for additional in [0, 1, 5, MAX_SIZE, 64, 1024] {
+        let mut s = to_compact(text);
+        s.reserve(additional);
+        let capacity = s.capacity();
+        assert!(capacity >= text.len() + additional);
+
+        // the first mutation must not need to copy the string again, e.g. for a `&'static str`
+        s.as_mut_str();
+        assert_eq!(s.capacity(), capacity);
+
+        // and the reserved space is usable without reallocating
+        for _ in 0..additional {
+            s.push('a');
+        }
+        assert_eq!(s.capacity(), capacity);
+        assert_eq!(s.len(), text.len() + additional);
+        assert!(s.starts_with(text));
+    }
+}
+
+#[test]
+fn test_shrink_to_fit_does_not_affect_clones() {
+    const TEXT: &str = "this string is long enough to be heap allocated";
+
+    let mut original = CompactString::with_capacity(256);
+    original.push_str(TEXT);
+
+    // clones never share a buffer
+    let mut copy = original.clone();
+    assert_ne!(original.as_ptr(), copy.as_ptr());
+    copy.reserve(256);
+    let copy_capacity = copy.capacity();
+
+    original.shrink_to_fit();
+    assert_eq!(original.capacity(), original.len());
+    assert_eq!(copy.capacity(), copy_capacity);
+
+    copy.shrink_to_fit();
+    assert_eq!(copy.capacity(), copy.len());
+    assert_eq!(original.capacity(), original.len());
+
+    assert_eq!(original, TEXT);
+    assert_eq!(copy, TEXT);
+}
+
+#[test]
+fn test_reserve_reporting() {
+    // inline, with spare capacity
+    let mut s = CompactString::new("hello");
+    assert!(!s.reserve_reporting(0));
+    assert!(!s.reserve_reporting(MAX_SIZE - s.len()));
+    assert!(!s.is_heap_allocated());
+
+    // inline, growing onto the heap
+    assert!(s.reserve_reporting(MAX_SIZE));
+    assert!(s.is_heap_allocated());
+
+    // heap, with spare capacity
+    let capacity = s.capacity();
+    assert!(!s.reserve_reporting(capacity - s.len()));
+    assert_eq!(s.capacity(), capacity);
+
+    // heap, growing
+    assert!(s.reserve_reporting(capacity));
+    assert!(s.capacity() > capacity);
+    assert_eq!(s, "hello");
+
+    // a `&'static str` that gets copied inline doesn't allocate...
+    let mut s = CompactString::from_static_str("this is a long static string");
+    let mut short = s.clone();
+    short.truncate(4);
+    assert_eq!(short.as_static_str(), Some("this"));
+    assert!(!short.reserve_reporting(1));
+    assert!(!short.is_heap_allocated());
+    // ...but one that needs to be copied onto the heap does
+    assert!(s.reserve_reporting(0));
+    assert!(s.is_heap_allocated());
+}
+
+#[test]
+fn test_reserve_exact() {
+    // inline, with enough spare capacity
+    let mut s = CompactString::new("hello");
+    s.reserve_exact(MAX_SIZE - s.len());
+    assert!(!s.is_heap_allocated());
+    assert_eq!(s.capacity(), MAX_SIZE);
+
+    // inline, growing onto the heap
+    s.reserve_exact(100);
+    assert!(s.is_heap_allocated());
+    assert_eq!(s.capacity(), 105);
+    assert_eq!(s, "hello");
+
+    // heap, growing without amortization
+    let text = "a".repeat(100);
+    let mut exact = CompactString::new(&text);
+    let mut amortized = exact.clone();
+    exact.reserve_exact(1);
+    amortized.reserve(1);
+    assert_eq!(exact.capacity(), 101);
+    assert!(amortized.capacity() > exact.capacity());
+    assert_eq!(exact, text);
+
+    // a `&'static str` gets copied into a buffer of exactly the right size
+    let mut s = CompactString::from_static_str("this is a long static string");
+    s.reserve_exact(10);
+    assert!(s.is_heap_allocated());
+    assert_eq!(s.capacity(), s.len() + 10);
+    assert_eq!(s, "this is a long static string");
+
+    // overflow is reported instead of panicking
+    assert!(s.try_reserve_exact(usize::MAX).is_err());
+    assert_eq!(s, "this is a long static string");
+}
+
+#[test]
+fn test_reserve_exact_then_extend() {
+    // 100 bytes, made of 1, 2, 3 and 4 byte chars
+    const TEXT: &str = concat!(
+        "hello wörld, ありがとう 🦀🦀, ",
+        "this is exactly 100 bytes long: 0123456789 abcdefghijklmnopq"
+    );
+    assert_eq!(TEXT.len(), 100);
+
+    fn check(extend: impl FnOnce(&mut CompactString)) {
+        let mut compact = CompactString::default();
+        compact.reserve_exact(TEXT.len());
+        assert_eq!(compact.capacity(), TEXT.len());
+        let ptr = compact.as_ptr();
+
+        extend(&mut compact);
+
+        // we had exactly enough room, so we didn't grow or reallocate
+        assert_eq!(compact, TEXT);
+        assert_eq!(compact.capacity(), TEXT.len());
+        assert_eq!(compact.as_ptr(), ptr);
+    }
+
+    check(|c| c.push_str(TEXT));
+    check(|c| TEXT.chars().for_each(|ch| c.push(ch)));
+    check(|c| c.extend(TEXT.chars()));
+    check(|c| c.extend(TEXT.chars().collect::<Vec<_>>().iter()));
+    check(|c| c.extend(TEXT.split_inclusive(' ')));
+    check(|c| c.extend(TEXT.split_inclusive(' ').map(String::from)));
+    check(|c| c.extend(TEXT.split_inclusive(' ').map(Box::<str>::from)));
+    check(|c| c.extend(TEXT.split_inclusive(' ').map(Cow::Borrowed)));
+    check(|c| c.extend(TEXT.split_inclusive(' ').map(CompactString::from)));
+    check(|c| *c += TEXT);
+    check(|c| core::fmt::Write::write_str(c, TEXT).unwrap());
+}
+
+#[test]
+fn test_with_capacity_for() {
+    let parts = ["hello", " ", "wörld", ", this string is ", "built from ", "🦀 parts"];
+    let lens = parts.iter().map(|part| part.len());
+
+    let mut compact = CompactString::with_capacity_for(lens.clone());
+    assert_eq!(compact.capacity(), lens.sum::<usize>());
+    let ptr = compact.as_ptr();
+
+    for part in &parts {
+        compact.push_str(part);
+    }
+
+    // we allocated exactly enough room up front, so the pushes never reallocated
+    assert_eq!(compact, parts.concat());
+    assert_eq!(compact.capacity(), compact.len());
+    assert_eq!(compact.as_ptr(), ptr);
+}
+
+#[test]
+fn test_with_capacity_for_inline() {
+    let compact = CompactString::with_capacity_for(vec![1, 2, 3]);
+    assert_eq!(compact.capacity(), MAX_SIZE);
+    assert!(!compact.is_heap_allocated());
+
+    let compact = CompactString::with_capacity_for(None);
+    assert_eq!(compact.capacity(), MAX_SIZE);
+}
+
+#[test]
+#[should_panic(expected = "Cannot allocate memory to hold CompactString")]
+fn test_with_capacity_for_overflow() {
+    let _ = CompactString::with_capacity_for(vec![usize::MAX, 1]);
+}
+
+#[test_case(CompactString::new(""); "empty")]
+#[test_case(CompactString::new("abc"); "inline")]
+#[test_case(CompactString::new("a long string that is heap allocated"); "heap")]
+#[test_case(CompactString::from_static_str("static"); "static")]
+fn test_spare_capacity_fill(mut compact: CompactString) {
+    // pretend we're a decoder that produces some number of bytes
+    fn decode(input: &[u8], out: &mut [core::mem::MaybeUninit<u8>]) -> usize {
+        for (dst, src) in out.iter_mut().zip(input) {
+            dst.write(src.to_ascii_uppercase());
+        }
+        input.len()
+    }
+
+    for input in ["xyz", "", "a decoded chunk that is long enough to spill onto the heap"] {
+        let expected = format!("{}{}", compact, input.to_ascii_uppercase());
+
+        compact.reserve_exact(input.len());
+        assert!(compact.capacity() >= expected.len());
+
+        // SAFETY: we reserved enough room for `input`, and we only write ASCII
+        unsafe {
+            let written = decode(input.as_bytes(), compact.spare_capacity_mut());
+            compact.set_len(compact.len() + written);
+        }
+        assert_eq!(compact, expected);
+        assert_eq!(compact.len(), expected.len());
+    }
+}
+
+#[test]
+fn test_spare_capacity_fill_to_capacity() {
+    // fill an inline string all the way up, including the byte that normally stores the length
+    let mut compact = CompactString::new("hello");
+    unsafe {
+        let spare = compact.spare_capacity_mut();
+        assert_eq!(spare.len(), MAX_SIZE - 5);
+        for byte in spare.iter_mut() {
+            byte.write(b'!');
+        }
+        compact.set_len(MAX_SIZE);
+    }
+    assert!(!compact.is_heap_allocated());
+    assert_eq!(compact, format!("hello{}", "!".repeat(MAX_SIZE - 5)));
+
+    // and there's no spare capacity left
+    assert!(unsafe { compact.spare_capacity_mut() }.is_empty());
+}
+
+#[test_case(CompactString::new(""); "empty")]
+#[test_case(CompactString::new("abc"); "inline")]
+#[test_case(CompactString::new("a long string that is heap allocated"); "heap")]
+#[test_case(CompactString::from_static_str("static"); "static")]
+fn test_reserve_and_spare(mut compact: CompactString) {
+    for input in ["xyz", "", "a decoded chunk that is long enough to spill onto the heap 🦀"] {
+        let expected = format!("{}{}", compact, input);
+
+        // SAFETY: we write exactly `input.len()` bytes of valid UTF-8, and then commit them
+        unsafe {
+            let spare = compact.reserve_and_spare(input.len());
+            assert!(spare.len() >= input.len());
+            for (dst, src) in spare.iter_mut().zip(input.as_bytes()) {
+                dst.write(*src);
+            }
+            compact.set_len(compact.len() + input.len());
+        }
+        assert_eq!(compact, expected);
+        assert_eq!(compact.as_static_str(), None);
+    }
+
+    // with enough spare capacity left we neither grow nor reallocate
+    let capacity = compact.capacity();
+    let ptr = compact.as_ptr();
+    let spare_len = capacity - compact.len();
+    let spare = unsafe { compact.reserve_and_spare(spare_len) };
+    assert_eq!(spare.len(), spare_len);
+    assert_eq!(compact.capacity(), capacity);
+    assert_eq!(compact.as_ptr(), ptr);
+}
+
+#[test_case(""; "empty")]
+#[test_case("short"; "inline")]
+#[test_case("a string that is long enough to be heap allocated"; "heap")]
+fn test_try_new(text: &'static str) {
+    let compact = CompactString::try_new(text).unwrap();
+    assert_eq!(compact, text);
+    assert_eq!(compact, CompactString::new(text));
+    assert_eq!(compact.is_heap_allocated(), text.len() > MAX_SIZE);
+}
+
+#[test]
+fn test_fallible_allocations_return_err() {
+    // none of these can be allocated, they return an error instead of panicking or aborting
+    assert!(CompactString::try_with_capacity(usize::MAX).is_err());
+    assert!(CompactString::try_with_capacity(isize::MAX as usize + 1).is_err());
+
+    let mut compact = CompactString::new("a string that is long enough to be heap allocated");
+    assert!(compact.try_reserve(usize::MAX).is_err());
+    assert!(compact.try_reserve(isize::MAX as usize).is_err());
+    assert!(compact.try_reserve_exact(usize::MAX).is_err());
+    assert_eq!(compact, "a string that is long enough to be heap allocated");
+}
+
+#[test_case("" ; "empty")]
+#[test_case("hello 🌎, you are nice" ; "inline")]
+#[test_case("Здравствуйте, a string that is long enough for the heap 🦀" ; "heap")]
+fn test_from_utf8_iter(text: &'static str) {
+    let compact = CompactString::from_utf8_iter(text.bytes()).unwrap();
+    assert_eq!(compact, text);
+    assert_eq!(compact.is_heap_allocated(), text.len() > MAX_SIZE);
+}
+
+#[test_case(b"\xFF" ; "invalid byte")]
+#[test_case(b"hello \xF0\x9F\xA6" ; "truncated trailing char")]
+#[test_case(b"a string that is long enough for the heap, \xC0" ; "invalid trailing byte")]
+fn test_from_utf8_iter_invalid(bytes: &[u8]) {
+    let err = CompactString::from_utf8_iter(bytes.iter().copied()).unwrap_err();
+    assert_eq!(Some(err), core::str::from_utf8(bytes).err());
+}
+
+#[test_case("" ; "empty")]
+#[test_case("hello 🌎, you are nice" ; "inline")]
+#[test_case("Здравствуйте, a string that is long enough for the heap 🦀" ; "heap")]
+fn test_from_utf8_exact(text: &'static str) {
+    let compact = CompactString::from_utf8_exact(text.bytes()).unwrap();
+    assert_eq!(compact, text);
+    assert_eq!(compact.is_heap_allocated(), text.len() > MAX_SIZE);
+
+    // we reserved exactly the bytes we needed, the number of allocations this makes is checked in
+    // `tests/allocations.rs`
+    if compact.is_heap_allocated() {
+        assert_eq!(compact.capacity(), text.len());
+    }
+}
+
+#[test_case(b"\xFF" ; "invalid byte")]
+#[test_case(b"hello \xF0\x9F\xA6" ; "truncated trailing char")]
+#[test_case(b"a string that is long enough for the heap, \xC0" ; "invalid trailing byte")]
+fn test_from_utf8_exact_invalid(bytes: &[u8]) {
+    let err = CompactString::from_utf8_exact(bytes.iter().copied()).unwrap_err();
+    assert_eq!(Some(err), core::str::from_utf8(bytes).err());
+}
+
+#[test]
+fn test_from_utf8_exact_wrong_len() {
+    /// An iterator that reports a wrong length, which safe code is allowed to do
+    struct WrongLen<I>(I, usize);
+
+    impl<I: Iterator<Item = u8>> Iterator for WrongLen<I> {
+        type Item = u8;
+
+        fn next(&mut self) -> Option<u8> {
+            self.0.next()
+        }
+
+        fn size_hint(&self) -> (usize, Option<usize>) {
+            (self.1, Some(self.1))
+        }
+    }
+
+    impl<I: Iterator<Item = u8>> ExactSizeIterator for WrongLen<I> {}
+
+    let text = "a string that is long enough to be heap allocated 🦀";
+    let too_short = CompactString::from_utf8_exact(WrongLen(text.bytes(), 3)).unwrap();
+    assert_eq!(too_short, text);
+
+    let too_long = CompactString::from_utf8_exact(WrongLen("hello".bytes(), 1000)).unwrap();
+    assert_eq!(too_long, "hello");
+}
+
+#[test]
+fn test_try_from_array() {
+    let ascii = CompactString::try_from(*b"hello world").unwrap();
+    assert_eq!(ascii, "hello world");
+
+    let multi_byte = CompactString::try_from([0xF0, 0x9F, 0xA6, 0x80, b'!']).unwrap();
+    assert_eq!(multi_byte, "🦀!");
+
+    let long =
+        CompactString::try_from(*b"an array that is long enough to be heap allocated").unwrap();
+    assert_eq!(long, "an array that is long enough to be heap allocated");
+    assert!(long.is_heap_allocated());
+
+    let empty = CompactString::try_from([]).unwrap();
+    assert_eq!(empty, "");
+
+    let invalid = [b'h', b'i', 0xF0, 0x9F];
+    let err = CompactString::try_from(invalid).unwrap_err();
+    assert_eq!(err.valid_up_to(), 2);
+    // the array ends in the middle of a char
+    assert_eq!(err.error_len(), None);
+}
+
+#[test]
+fn test_from_utf8_array_unchecked() {
+    let compact = unsafe { CompactString::from_utf8_array_unchecked(*b"hello") };
+    assert_eq!(compact, "hello");
+
+    let compact = unsafe { CompactString::from_utf8_array_unchecked([0xC3, 0xBC, b'!']) };
+    assert_eq!(compact, "ü!");
+
+    let bytes = [b'a'; 100];
+    let compact = unsafe { CompactString::from_utf8_array_unchecked(bytes) };
+    assert_eq!(compact.as_bytes(), bytes);
+    assert!(compact.is_heap_allocated());
+}
+
+#[test]
+fn test_from_utf8_unchecked_owned() {
+    // the allocation is adopted as is, even for short strings
+    let bytes = "hello 🌎".as_bytes().to_vec();
+    let ptr = bytes.as_ptr();
+    let compact = unsafe { CompactString::from_utf8_unchecked_owned(bytes) };
+    assert_eq!(compact, "hello 🌎");
+    assert_eq!(compact.as_ptr(), ptr);
+    assert!(compact.is_heap_allocated());
+
+    let mut bytes = Vec::with_capacity(100);
+    bytes.extend_from_slice("a long string that is heap allocated, ü".as_bytes());
+    let ptr = bytes.as_ptr();
+    let compact = unsafe { CompactString::from_utf8_unchecked_owned(bytes) };
+    assert_eq!(compact, "a long string that is heap allocated, ü");
+    assert_eq!(compact.as_ptr(), ptr);
+    assert_eq!(compact.capacity(), 100);
+
+    let compact = unsafe { CompactString::from_utf8_unchecked_owned(Vec::new()) };
+    assert_eq!(compact, "");
+    assert!(!compact.is_heap_allocated());
+}
+
 #[test]
 fn test_from_utf8_unchecked_sanity() {
     let text = "hello 🌎, you are nice";
@@ -1811,34 +2610,182 @@ fn test_into_arc() {
     assert_eq!(long, &*arc);
 }
 
-#[test]
-fn test_into_rc() {
-    let short = "short";
-    let long = "i am a long string that will be allocated on the heap";
-    let rc = alloc::rc::Rc::<str>::from(CompactString::new(short));
-    assert_eq!(short, &*rc);
-    let rc = alloc::rc::Rc::<str>::from(CompactString::new(long));
-    assert_eq!(long, &*rc);
+#[test_case(""; "empty")]
+#[test_case("short"; "inline")]
+#[test_case("i am a long string that will be allocated on the heap"; "heap")]
+fn test_arc_str_roundtrip(text: &'static str) {
+    let arc = CompactString::new(text).into_arc_str();
+    assert_eq!(&*arc, text);
+
+    let compact = CompactString::from_arc_str(arc);
+    assert_eq!(compact, text);
+    assert_eq!(compact.is_heap_allocated(), text.len() > MAX_SIZE);
 }
 
 #[test]
-fn test_into_error() {
-    let short = "short";
-    let long = "i am a long string that will be allocated on the heap";
-    let short_error_ss =
-        Box::<dyn std::error::Error + Send + Sync>::from(CompactString::new(short));
-    assert_eq!(short, format!("{short_error_ss}"));
-    assert_eq!(format!("{short:?}"), format!("{short_error_ss:?}"));
-    let long_error_ss = Box::<dyn std::error::Error + Send + Sync>::from(CompactString::new(long));
-    assert_eq!(long, format!("{long_error_ss}"));
-    assert_eq!(format!("{long:?}"), format!("{long_error_ss:?}"));
+fn test_clone_does_not_share() {
+    let original = CompactString::new("i am a long string that gets cloned, not shared");
+    let mut clone = original.clone();
+    assert!(clone.is_heap_allocated());
+    assert_ne!(clone.as_ptr(), original.as_ptr());
+
+    // each clone owns its own buffer, so mutating one never affects the other
+    clone.make_ascii_uppercase();
+    assert_eq!(clone, "I AM A LONG STRING THAT GETS CLONED, NOT SHARED");
+    assert_eq!(original, "i am a long string that gets cloned, not shared");
+}
 
-    let short_error = Box::<dyn std::error::Error>::from(CompactString::new(short));
-    assert_eq!(short, format!("{short_error}"));
-    assert_eq!(format!("{short:?}"), format!("{short_error:?}"));
-    let long_error = Box::<dyn std::error::Error>::from(CompactString::new(long));
-    assert_eq!(long, format!("{long_error}"));
-    assert_eq!(format!("{long:?}"), format!("{long_error:?}"));
+#[test]
+fn test_mutations_only_copy_static_strs() {
+    let text = "a &'static str that is long enough to be heap allocated";
+
+    // a fresh heap allocated string is mutated in place
+    let mut compact = CompactString::new(text);
+    assert_eq!(compact.as_static_str(), None);
+    let ptr = compact.as_ptr();
+    compact.make_ascii_uppercase();
+    assert_eq!(compact.as_ptr(), ptr);
+
+    // a clone has its own buffer, so mutating either of them is still done in place
+    let mut clone = compact.clone();
+    let clone_ptr = clone.as_ptr();
+    compact.make_ascii_lowercase();
+    clone.make_ascii_lowercase();
+    assert_eq!(compact.as_ptr(), ptr);
+    assert_eq!(clone.as_ptr(), clone_ptr);
+    drop(clone);
+    compact.make_ascii_uppercase();
+    assert_eq!(compact.as_ptr(), ptr);
+
+    // only a `&'static str` gets copied by the first mutation, after which it's owned
+    let mut compact = CompactString::from_static_str(text);
+    assert_eq!(compact.as_static_str(), Some(text));
+    compact.make_ascii_uppercase();
+    assert_eq!(compact.as_static_str(), None);
+    assert_ne!(compact.as_ptr(), text.as_ptr());
+    let ptr = compact.as_ptr();
+    compact.make_ascii_lowercase();
+    assert_eq!(compact, text);
+    assert_eq!(compact.as_ptr(), ptr);
+}
+
+#[test]
+fn test_reserve_static_str_copies_once() {
+    const TEXT: &str = "a &'static str that is long enough to be heap allocated";
+
+    let mut compact = CompactString::from_static_str(TEXT);
+    compact.reserve(10);
+
+    // reserving copied the string straight into a buffer we own, with the requested capacity
+    assert_eq!(compact, TEXT);
+    assert_eq!(compact.as_static_str(), None);
+    assert!(compact.is_heap_allocated());
+    assert!(compact.capacity() >= TEXT.len() + 10);
+    let ptr = compact.as_ptr();
+    let capacity = compact.capacity();
+
+    // so the following pushes are done in place, without copying or growing again
+    for _ in 0..10 {
+        compact.push('!');
+    }
+    assert_eq!(compact, format!("{}!!!!!!!!!!", TEXT));
+    assert_eq!(compact.as_ptr(), ptr);
+    assert_eq!(compact.capacity(), capacity);
+}
+
+#[test]
+fn test_clone_is_tightly_sized() {
+    // a transient, over-allocated buffer
+    let mut transient = CompactString::with_capacity(1024);
+    transient.push_str("a string that is long enough to need the heap, even when shrunk");
+    assert!(transient.capacity() >= 1024);
+
+    let clone = transient.clone();
+    assert_eq!(clone, transient);
+    assert_eq!(clone.capacity(), clone.len());
+    assert_ne!(clone.as_ptr(), transient.as_ptr());
+
+    // short strings get inlined, no matter the capacity of the source
+    let mut transient = CompactString::with_capacity(1024);
+    transient.push_str("short");
+    let clone = transient.clone();
+    assert_eq!(clone, "short");
+    assert!(!clone.is_heap_allocated());
+}
+
+#[test]
+fn test_clone_into_reuses_target() {
+    use alloc::borrow::ToOwned;
+
+    let source = CompactString::new("i am the source, and long enough to be heap allocated");
+
+    // `ToOwned::clone_into` forwards to `Clone::clone_from`, which reuses the target's buffer
+    let mut target = CompactString::with_capacity(128);
+    target.push_str("i am the previous contents of the target");
+    let ptr = target.as_ptr();
+    source.clone_into(&mut target);
+    assert_eq!(target, source);
+    assert_eq!(target.as_ptr(), ptr);
+    assert_ne!(target.as_ptr(), source.as_ptr());
+
+    // a target that's too small gets reallocated
+    let mut target = CompactString::new("too small");
+    source.clone_into(&mut target);
+    assert_eq!(target, source);
+    assert_ne!(target.as_ptr(), source.as_ptr());
+
+    // cloning an inline string into a heap allocated target frees the target's old buffer
+    let mut target = CompactString::with_capacity(128);
+    CompactString::new("short").clone_into(&mut target);
+    assert_eq!(target, "short");
+    assert!(!target.is_heap_allocated());
+}
+
+#[test]
+fn test_from_arc_str_is_not_shared() {
+    use alloc::sync::Arc;
+
+    let arc: Arc<str> = Arc::from("i am a long string, shared by an Arc");
+    let other = Arc::clone(&arc);
+
+    let mut compact = CompactString::from_arc_str(arc);
+    assert_ne!(compact.as_ptr(), other.as_ptr());
+
+    // mutating the CompactString never affects the contents of the Arc
+    compact.make_ascii_uppercase();
+    compact.push_str("!");
+    assert_eq!(compact, "I AM A LONG STRING, SHARED BY AN ARC!");
+    assert_eq!(&*other, "i am a long string, shared by an Arc");
+}
+
+#[test]
+fn test_into_rc() {
+    let short = "short";
+    let long = "i am a long string that will be allocated on the heap";
+    let rc = alloc::rc::Rc::<str>::from(CompactString::new(short));
+    assert_eq!(short, &*rc);
+    let rc = alloc::rc::Rc::<str>::from(CompactString::new(long));
+    assert_eq!(long, &*rc);
+}
+
+#[test]
+fn test_into_error() {
+    let short = "short";
+    let long = "i am a long string that will be allocated on the heap";
+    let short_error_ss =
+        Box::<dyn std::error::Error + Send + Sync>::from(CompactString::new(short));
+    assert_eq!(short, format!("{short_error_ss}"));
+    assert_eq!(format!("{short:?}"), format!("{short_error_ss:?}"));
+    let long_error_ss = Box::<dyn std::error::Error + Send + Sync>::from(CompactString::new(long));
+    assert_eq!(long, format!("{long_error_ss}"));
+    assert_eq!(format!("{long:?}"), format!("{long_error_ss:?}"));
+
+    let short_error = Box::<dyn std::error::Error>::from(CompactString::new(short));
+    assert_eq!(short, format!("{short_error}"));
+    assert_eq!(format!("{short:?}"), format!("{short_error:?}"));
+    let long_error = Box::<dyn std::error::Error>::from(CompactString::new(long));
+    assert_eq!(long, format!("{long_error}"));
+    assert_eq!(format!("{long:?}"), format!("{long_error:?}"));
 }
 
 #[test]
@@ -1851,6 +2798,36 @@ fn test_into_box_str() {
     assert_eq!(long, &*l);
 }
 
+#[test_case(""; "empty")]
+#[test_case("short"; "inline")]
+#[test_case("i am a long string that will be allocated on the heap"; "heap")]
+fn test_into_boxed_str(control: &'static str) {
+    let b = CompactString::new(control).into_boxed_str();
+    assert_eq!(&*b, control);
+
+    let b = CompactString::from_static_str(control).into_boxed_str();
+    assert_eq!(&*b, control);
+
+    // excess capacity is dropped
+    let mut compact = CompactString::new(control);
+    compact.reserve(100);
+    let b = compact.into_boxed_str();
+    assert_eq!(&*b, control);
+    assert_eq!(b.len(), control.len());
+}
+
+#[test]
+fn test_into_boxed_str_clone_unaffected() {
+    let original = CompactString::new("i am a long string that will be allocated on the heap");
+    let clone = original.clone();
+
+    let mut b = original.into_boxed_str();
+    b.make_ascii_uppercase();
+
+    assert_eq!(&*b, "I AM A LONG STRING THAT WILL BE ALLOCATED ON THE HEAP");
+    assert_eq!(clone, "i am a long string that will be allocated on the heap");
+}
+
 #[test]
 fn test_into_os_string() {
     let short = "short";
@@ -1894,6 +2871,57 @@ fn test_into_vec_u8() {
     assert_eq!(&l, long.as_bytes());
 }
 
+#[test]
+fn test_outward_conversions_reuse_heap_buffer() {
+    const TEXT: &str = "i am a long string that will be allocated on the heap";
+
+    let compact = CompactString::new(TEXT);
+    let ptr = compact.as_ptr();
+    let string = String::from(compact);
+    assert_eq!(string, TEXT);
+    assert_eq!(string.as_ptr(), ptr);
+
+    let compact = CompactString::new(TEXT);
+    let ptr = compact.as_ptr();
+    let boxed = Box::<str>::from(compact);
+    assert_eq!(&*boxed, TEXT);
+    assert_eq!(boxed.as_ptr(), ptr);
+
+    let compact = CompactString::new(TEXT);
+    let ptr = compact.as_ptr();
+    let bytes = Vec::<u8>::from(compact);
+    assert_eq!(bytes, TEXT.as_bytes());
+    assert_eq!(bytes.as_ptr(), ptr);
+
+    // inline strings and `&'static str`s have no heap buffer to reuse, so they get copied
+    let compact = CompactString::from_static_str(TEXT);
+    let bytes = Vec::<u8>::from(compact);
+    assert_eq!(bytes, TEXT.as_bytes());
+    assert_ne!(bytes.as_ptr(), TEXT.as_ptr());
+    assert_eq!(String::from(CompactString::new("short")), "short");
+}
+
+#[test_case(""; "empty")]
+#[test_case("short"; "inline")]
+#[test_case("this string is long enough to be heap allocated"; "heap")]
+fn test_from_borrowed_string_and_box_str(text: &'static str) {
+    let string = String::from(text);
+    let compact = CompactString::from(&string);
+    assert_eq!(compact, text);
+    assert_eq!(compact.is_heap_allocated(), text.len() > MAX_SIZE);
+    // the borrowed buffer is copied, not adopted
+    assert_ne!(compact.as_ptr(), string.as_ptr());
+
+    let box_str = String::from(text).into_boxed_str();
+    let compact = CompactString::from(&box_str);
+    assert_eq!(compact, text);
+    assert_eq!(compact.is_heap_allocated(), text.len() > MAX_SIZE);
+    assert_ne!(compact.as_ptr(), box_str.as_ptr());
+
+    assert_eq!(&*string, text);
+    assert_eq!(&*box_str, text);
+}
+
 #[test]
 fn test_from_string_buffer_inlines_on_push() {
     let mut compact = CompactString::from_string_buffer("hello".to_string());
@@ -1925,6 +2953,37 @@ fn test_alloc_excessively_long_string() {
     CompactString::with_capacity((1 << 56) - 2);
 }
 
+// `core::slice::from_raw_parts` is only a `const fn` since Rust 1.64
+#[rustversion::since(1.64)]
+#[test]
+fn test_const_as_str() {
+    // a `CompactString` can't be dropped at compile time, so we borrow from `static`s
+    static EMPTY: CompactString = CompactString::new_inline("");
+    static SHORT: CompactString = CompactString::new_inline("hello 🦀");
+    static LONG: CompactString =
+        CompactString::from_static_str("a &'static str that is too long to be inlined");
+
+    static EMPTY_STR: &str = EMPTY.const_as_str();
+    static SHORT_STR: &str = SHORT.const_as_str();
+    static LONG_STR: &str = LONG.const_as_str();
+
+    assert_eq!(EMPTY_STR, "");
+    assert_eq!(SHORT_STR, "hello 🦀");
+    assert_eq!(LONG_STR, "a &'static str that is too long to be inlined");
+
+    // at runtime every kind of string works
+    for text in ["", "abc", "a string that goes on the heap, 🦀"] {
+        for mut compact in [CompactString::new(text), CompactString::from_static_str(text)] {
+            assert_eq!(compact.const_as_str(), text);
+            compact.push_str(" and some more");
+            assert_eq!(compact.const_as_str(), compact.as_str());
+        }
+    }
+
+    let max = CompactString::new("a".repeat(MAX_SIZE));
+    assert_eq!(max.const_as_str(), max.as_str());
+}
+
 // This feature was enabled by <https://github.com/rust-lang/rust/pull/94075> which was first
 // released in Rust 1.65.
 #[rustversion::since(1.65)]
@@ -1968,3 +3027,1498 @@ fn test_is_empty() {
         }
     }
 }
+
+#[test_case(CompactString::from; "inline")]
+#[test_case(CompactString::from_static_str; "static_str")]
+fn test_find_rfind(to_compact: fn(&'static str) -> CompactString) {
+    // `find` and `rfind` come from `str` through `Deref`, so they accept any pattern and return
+    // byte offsets, not char offsets
+    let compact = to_compact("🦀 crab 🦀 crab");
+    let control = "🦀 crab 🦀 crab";
+
+    assert_eq!(compact.find("crab"), Some(5));
+    assert_eq!(compact.rfind("crab"), Some(15));
+    assert_eq!(compact.find("crab"), control.find("crab"));
+    assert_eq!(compact.rfind("crab"), control.rfind("crab"));
+
+    assert_eq!(compact.find('🦀'), Some(0));
+    assert_eq!(compact.rfind('🦀'), Some(10));
+    assert_eq!(compact.find(char::is_whitespace), Some(4));
+
+    assert_eq!(compact.find("lobster"), None);
+    assert_eq!(compact.rfind("lobster"), None);
+}
+
+#[test]
+fn test_find_rfind_heap() {
+    let compact = CompactString::new("this is a long string, long enough to be on the heap");
+    assert!(compact.is_heap_allocated());
+
+    assert_eq!(compact.find("long"), Some(10));
+    assert_eq!(compact.rfind("long"), Some(23));
+    assert_eq!(compact.find("short"), None);
+}
+
+#[test]
+fn test_debug_alternate() {
+    let short = CompactString::new("hello");
+    assert_eq!(format!("{short:?}"), "\"hello\"");
+
+    let expected = [
+        "CompactString {".to_string(),
+        "    string: \"hello\",".to_string(),
+        "    len: 5,".to_string(),
+        format!("    capacity: {MAX_SIZE},"),
+        "    heap_allocated: false,".to_string(),
+        "}".to_string(),
+    ]
+    .join("\n");
+    assert_eq!(format!("{short:#?}"), expected);
+
+    let mut long = CompactString::new("i am a long string that will be allocated on the heap");
+    long.reserve(100);
+    let capacity = long.capacity();
+    assert_eq!(
+        format!("{long:?}"),
+        "\"i am a long string that will be allocated on the heap\""
+    );
+
+    let pretty = format!("{long:#?}");
+    assert!(pretty.contains("len: 53"));
+    assert!(pretty.contains(&format!("capacity: {capacity}")));
+    assert!(pretty.contains("heap_allocated: true"));
+}
+
+// Miri reports leaked memory as an error
+#[test_case(""; "empty")]
+#[test_case("hello world"; "inline")]
+#[test_case("i am a long string that will be allocated on the heap"; "heap")]
+#[cfg_attr(miri, ignore)]
+fn test_leak(control: &'static str) {
+    let compact = CompactString::new(control);
+    let leaked: &'static mut str = compact.leak();
+    assert_eq!(leaked, control);
+
+    let compact = CompactString::from_static_str(control);
+    let leaked: &'static mut str = compact.leak();
+    assert_eq!(leaked, control);
+}
+
+#[test]
+#[cfg_attr(miri, ignore)]
+fn test_leak_clone_unaffected() {
+    let original = CompactString::new("i am a long string that will be allocated on the heap");
+    let clone = original.clone();
+
+    let leaked = original.leak();
+    leaked.make_ascii_uppercase();
+
+    assert_eq!(leaked, "I AM A LONG STRING THAT WILL BE ALLOCATED ON THE HEAP");
+    assert_eq!(clone, "i am a long string that will be allocated on the heap");
+}
+
+#[test]
+fn test_matches_match_indices() {
+    // `matches` and `match_indices` come from `str` through `Deref`, with the same
+    // non-overlapping semantics
+    let compact = CompactString::new("abc 🦀 abc 🦀 abc");
+
+    assert_eq!(compact.matches("abc").count(), 3);
+    assert_eq!(
+        compact.match_indices("abc").collect::<Vec<_>>(),
+        [(0, "abc"), (9, "abc"), (18, "abc")],
+    );
+    assert_eq!(compact.matches('🦀').count(), 2);
+
+    // candidates overlap, but only non-overlapping matches are returned
+    let compact = CompactString::new("aaaaa");
+    assert_eq!(compact.matches("aa").collect::<Vec<_>>(), ["aa", "aa"]);
+    assert_eq!(
+        compact.match_indices("aa").collect::<Vec<_>>(),
+        [(0, "aa"), (2, "aa")],
+    );
+
+    assert_eq!(compact.matches("b").count(), 0);
+    assert_eq!(compact.match_indices("b").next(), None);
+}
+
+#[test_case(2; "short")]
+#[test_case(MAX_SIZE; "max inline")]
+#[test_case(MAX_SIZE + 1; "heap")]
+#[test_case(4096; "long")]
+fn test_repeat_single_byte(n: usize) {
+    let compact = CompactString::new("a").repeat(n);
+    assert_eq!(compact, "a".repeat(n));
+    assert_eq!(compact.len(), n);
+    assert_allocated_properly(&compact);
+
+    // a single multi-byte char goes through the general path
+    let compact = CompactString::new("é").repeat(n);
+    assert_eq!(compact, "é".repeat(n));
+}
+
+#[test]
+fn test_get_unchecked() {
+    // `get_unchecked` comes from `str` through `Deref`, so it takes any range type
+    let compact = CompactString::new("🦀 hello world, i am on the heap 🦀");
+    assert!(compact.is_heap_allocated());
+
+    for (start, end) in [(0, 4), (5, 10), (4, 5), (0, compact.len()), (7, 7)] {
+        assert!(compact.is_char_boundary(start) && compact.is_char_boundary(end));
+        // SAFETY: we just checked that the range is in bounds and lies on char boundaries
+        let unchecked = unsafe { compact.get_unchecked(start..end) };
+        assert_eq!(Some(unchecked), compact.get(start..end));
+    }
+
+    // SAFETY: the range is in bounds and lies on char boundaries
+    assert_eq!(unsafe { compact.get_unchecked(5..) }, &compact[5..]);
+}
+
+#[test]
+fn test_repeat_empty_does_not_allocate() {
+    let compact = CompactString::from("").repeat(1000);
+    assert_eq!(compact, "");
+    assert!(!compact.is_heap_allocated());
+
+    // even if the source string is heap allocated
+    let empty_heap = CompactString::with_capacity(1000);
+    assert!(empty_heap.is_heap_allocated());
+    let compact = empty_heap.repeat(1000);
+    assert_eq!(compact, "");
+    assert!(!compact.is_heap_allocated());
+
+    let compact =
+        CompactString::new("i am a long string that will be allocated on the heap").repeat(0);
+    assert_eq!(compact, "");
+    assert!(!compact.is_heap_allocated());
+}
+
+#[test_case(CompactString::from; "inline")]
+#[test_case(CompactString::from_static_str; "static_str")]
+fn test_into_iter(to_compact: fn(&'static str) -> CompactString) {
+    const TEXT: &str = "🦀 ümlauts and a string that is long enough to be heap allocated 🦀";
+    let compact = to_compact(TEXT);
+
+    let chars: Vec<char> = compact.clone().into_iter().collect();
+    assert_eq!(chars, TEXT.chars().collect::<Vec<_>>());
+
+    let mut collected = String::new();
+    for c in compact.clone() {
+        collected.push(c);
+    }
+    assert_eq!(collected, TEXT);
+
+    let mut iter = compact.clone().into_iter();
+    assert_eq!(iter.next(), Some('🦀'));
+    assert_eq!(iter.next_back(), Some('🦀'));
+    assert_eq!(iter.next(), Some(' '));
+    assert_eq!(iter.next(), Some('ü'));
+    assert_eq!(iter.as_str(), &TEXT[7..TEXT.len() - 4]);
+    assert_eq!(iter.clone().count(), TEXT.chars().count() - 4);
+    // drop the iterator early, which frees the buffer
+    drop(iter);
+
+    let reversed: String = compact.into_iter().rev().collect();
+    assert_eq!(reversed, TEXT.chars().rev().collect::<String>());
+}
+
+#[test_case(CompactString::from; "inline")]
+#[test_case(CompactString::from_static_str; "static_str")]
+fn test_into_bytes_iter(to_compact: fn(&'static str) -> CompactString) {
+    const TEXT: &str = "🦀 ümlauts and a string that is long enough to be heap allocated 🦀";
+    let compact = to_compact(TEXT);
+
+    let bytes: Vec<u8> = compact.clone().into_bytes_iter().collect();
+    assert_eq!(bytes, TEXT.as_bytes());
+    assert_eq!(compact.clone().into_bytes_iter().len(), TEXT.len());
+
+    let mut iter = compact.clone().into_bytes_iter();
+    assert_eq!(iter.next(), Some(0xF0));
+    assert_eq!(iter.next_back(), Some(0x80));
+    assert_eq!(iter.nth(4), Some(0xC3));
+    assert_eq!(iter.as_slice(), &TEXT.as_bytes()[6..TEXT.len() - 1]);
+    assert_eq!(iter.len(), TEXT.len() - 7);
+    assert_eq!(iter.clone().count(), TEXT.len() - 7);
+    assert_eq!(iter.clone().last(), Some(0xA6));
+    // drop the iterator early, which frees the buffer
+    drop(iter);
+
+    let mut iter = compact.clone().into_bytes_iter();
+    assert_eq!(iter.nth(TEXT.len()), None);
+    assert_eq!(iter.next(), None);
+    assert_eq!(iter.next_back(), None);
+
+    let reversed: Vec<u8> = compact.into_bytes_iter().rev().collect();
+    assert_eq!(reversed, TEXT.bytes().rev().collect::<Vec<_>>());
+}
+
+#[test]
+fn test_into_bytes_iter_empty() {
+    let mut iter = CompactString::new("").into_bytes_iter();
+    assert_eq!(iter.len(), 0);
+    assert_eq!(iter.next(), None);
+    assert_eq!(iter.next_back(), None);
+}
+
+#[test]
+fn test_strip_prefix_suffix() {
+    // `strip_prefix` and `strip_suffix` come from `str` through `Deref`, and borrow from the
+    // `CompactString`
+    let compact = CompactString::new("--verbose=🦀");
+
+    assert_eq!(compact.strip_prefix("--"), Some("verbose=🦀"));
+    assert_eq!(compact.strip_prefix("-v"), None);
+    assert_eq!(compact.strip_prefix(""), Some("--verbose=🦀"));
+    assert_eq!(compact.strip_prefix('-'), Some("-verbose=🦀"));
+
+    assert_eq!(compact.strip_suffix("=🦀"), Some("--verbose"));
+    assert_eq!(compact.strip_suffix("=🐍"), None);
+    assert_eq!(compact.strip_suffix(""), Some("--verbose=🦀"));
+}
+
+#[test]
+fn test_pop_and_shrink() {
+    let text = "a".repeat(256);
+
+    let mut popped = CompactString::new(&text);
+    let mut shrunk = CompactString::new(&text);
+    let initial_capacity = shrunk.capacity();
+
+    for _ in 0..200 {
+        assert_eq!(popped.pop(), Some('a'));
+        assert_eq!(shrunk.pop_and_shrink(), Some('a'));
+        assert_eq!(popped, shrunk);
+        assert!(shrunk.capacity() >= shrunk.len());
+    }
+
+    // `pop` never releases memory
+    assert_eq!(popped.capacity(), initial_capacity);
+    // ...but `pop_and_shrink` does
+    assert!(shrunk.capacity() < initial_capacity);
+    assert!(shrunk.capacity() <= shrunk.len() * 4);
+
+    // eventually the string gets inlined
+    while shrunk.pop_and_shrink().is_some() {}
+    assert_eq!(shrunk, "");
+    assert!(!shrunk.is_heap_allocated());
+    assert_eq!(shrunk.pop_and_shrink(), None);
+}
+
+fn panic_message(f: impl FnOnce() + std::panic::UnwindSafe) -> String {
+    let payload = std::panic::catch_unwind(f).unwrap_err();
+    match payload.downcast::<String>() {
+        Ok(msg) => *msg,
+        Err(payload) => String::from(*payload.downcast::<&str>().unwrap()),
+    }
+}
+
+#[test_case("🦄 corn", 1 ; "inline")]
+#[test_case("🦄 this string is long enough to be heap allocated", 2 ; "heap")]
+#[test_case("ab", 10 ; "out of bounds")]
+fn test_char_boundary_panic_messages(text: &'static str, idx: usize) {
+    // CompactString panics with the same message as slicing a `str` at `idx`
+    let expected = panic_message(move || {
+        let _ = &text[idx..];
+    });
+    if idx <= text.len() {
+        assert!(expected.contains(&format!("byte index {idx} is not a char boundary")));
+        assert!(expected.contains("it is inside '🦄' (bytes 0..4)"));
+    }
+
+    let msg = panic_message(move || CompactString::new(text).insert(idx, 'x'));
+    assert_eq!(msg, expected);
+
+    let msg = panic_message(move || CompactString::new(text).insert_str(idx, "xyz"));
+    assert_eq!(msg, expected);
+
+    let msg = panic_message(move || {
+        CompactString::new(text).remove(idx);
+    });
+    assert_eq!(msg, expected);
+
+    let msg = panic_message(move || {
+        let _ = &CompactString::new(text)[idx..];
+    });
+    assert_eq!(msg, expected);
+
+    if idx < text.len() {
+        let msg = panic_message(move || CompactString::new(text).truncate(idx));
+        assert_eq!(msg, expected);
+    }
+}
+
+#[test_case("hello world", &["hello", "world"] ; "single space")]
+#[test_case("hello    world", &["hello", "world"] ; "multiple spaces")]
+#[test_case("hello\tworld\nfoo\r\nbar", &["hello", "world", "foo", "bar"] ; "tabs and newlines")]
+#[test_case("  \t hello world \n", &["hello", "world"] ; "leading and trailing")]
+#[test_case("hello\u{3000}world\u{a0}🦀", &["hello", "world", "🦀"] ; "unicode whitespace")]
+#[test_case("no_whitespace_in_this_rather_long_input", &["no_whitespace_in_this_rather_long_input"] ; "no whitespace")]
+#[test_case(" \t\n ", &[] ; "only whitespace")]
+fn test_split_whitespace(text: &str, expected: &[&str]) {
+    let compact = CompactString::new(text);
+    let tokens: Vec<&str> = compact.split_whitespace().collect();
+    assert_eq!(tokens, expected);
+    assert_eq!(tokens, text.split_whitespace().collect::<Vec<_>>());
+}
+
+#[test]
+fn test_eq_cow() {
+    let compact = CompactString::new("hello world, this is a longer string");
+
+    let borrowed: Cow<'_, str> = Cow::Borrowed("hello world, this is a longer string");
+    let owned: Cow<'_, str> = Cow::Owned(String::from("hello world, this is a longer string"));
+    let other: Cow<'_, str> = Cow::Borrowed("hello");
+
+    assert_eq!(compact, borrowed);
+    assert_eq!(compact, owned);
+    assert_ne!(compact, other);
+    assert_eq!(borrowed, compact);
+    assert_eq!(owned, compact);
+    assert_ne!(other, compact);
+}
+
+#[test]
+fn test_eq_os_str() {
+    use std::ffi::{
+        OsStr,
+        OsString,
+    };
+
+    let compact = CompactString::new("config.toml");
+    let name = OsString::from("config.toml");
+
+    assert!(compact == *name.as_os_str());
+    assert!(*name.as_os_str() == compact);
+    assert!(name.as_os_str() == compact);
+    assert!(compact != *OsStr::new("Cargo.toml"));
+    assert!(OsStr::new("Cargo.toml") != compact);
+
+    #[cfg(unix)]
+    {
+        use std::os::unix::ffi::OsStrExt;
+
+        // not valid UTF-8, so it can never equal a CompactString
+        let invalid = OsStr::from_bytes(b"config\xFF.toml");
+        assert!(compact != *invalid);
+        assert!(invalid != compact);
+        assert!(CompactString::new("config\u{FFFD}.toml") != *invalid);
+    }
+}
+
+#[allow(clippy::op_ref)]
+#[test_case("HELLO"; "ascii")]
+#[test_case("grüße, 🦀"; "multibyte")]
+#[test_case("a longer string that is allocated on the heap"; "heap")]
+fn test_eq_bytes(text: &'static str) {
+    let compact = CompactString::new(text);
+    let bytes: &[u8] = text.as_bytes();
+
+    assert!(compact == *bytes);
+    assert!(*bytes == compact);
+    assert!(bytes == compact);
+    assert!(compact.eq_bytes(bytes));
+    assert!(CompactString::from_static_str(text).eq_bytes(bytes));
+
+    // differing in a single byte, or in length
+    let mut differing = bytes.to_vec();
+    *differing.last_mut().unwrap() ^= 1;
+    assert!(compact != *differing);
+    assert!(*differing != compact);
+    assert!(compact != bytes[..bytes.len() - 1]);
+    assert!(&bytes[1..] != compact);
+    assert!(!compact.eq_bytes(&differing));
+    assert!(!compact.eq_bytes(&bytes[..bytes.len() - 1]));
+    assert!(!compact.eq_bytes(b""));
+}
+
+// `fits_inline` can be evaluated at compile time
+const _: () = assert!(CompactString::fits_inline(0));
+const _: () = assert!(CompactString::fits_inline(MAX_SIZE));
+const _: () = assert!(!CompactString::fits_inline(MAX_SIZE + 1));
+
+#[test_case(0; "empty")]
+#[test_case(MAX_SIZE - 1; "one below")]
+#[test_case(MAX_SIZE; "boundary")]
+#[test_case(MAX_SIZE + 1; "one over")]
+#[test_case(1024; "large")]
+fn test_fits_inline(len: usize) {
+    let compact = CompactString::new("a".repeat(len));
+    assert_eq!(CompactString::fits_inline(len), !compact.is_heap_allocated());
+    assert_eq!(CompactString::fits_inline(len), len <= MAX_SIZE);
+}
+
+#[test]
+fn test_capacity_for() {
+    let lengths = (0..=MAX_SIZE * 4).chain([100, 1024, 4096, 65_536]);
+    for len in lengths {
+        let text = "a".repeat(len);
+        let predicted = CompactString::capacity_for(len);
+
+        assert!(predicted >= len);
+        assert_eq!(CompactString::new(&text).capacity(), predicted, "len {len}");
+        assert_eq!(CompactString::from(text.as_str()).capacity(), predicted, "len {len}");
+    }
+
+    assert_eq!(CompactString::capacity_for(0), MAX_SIZE);
+    assert_eq!(CompactString::capacity_for(MAX_SIZE), MAX_SIZE);
+    assert!(CompactString::capacity_for(MAX_SIZE + 1) > MAX_SIZE);
+}
+
+#[test_case(CompactString::from; "inline")]
+#[test_case(CompactString::from_static_str; "static_str")]
+fn test_char_at(to_compact: fn(&'static str) -> CompactString) {
+    let s = to_compact("h€llo 🦀");
+
+    // first char
+    assert_eq!(s.char_at(0), Some('h'));
+    // start of an interior multi-byte char
+    assert_eq!(s.char_at(1), Some('€'));
+    assert_eq!(s.char_at(4), Some('l'));
+    assert_eq!(s.char_at(8), Some('🦀'));
+    // interior bytes of multi-byte chars
+    assert_eq!(s.char_at(2), None);
+    assert_eq!(s.char_at(3), None);
+    assert_eq!(s.char_at(11), None);
+    // out of range
+    assert_eq!(s.char_at(s.len()), None);
+    assert_eq!(s.char_at(s.len() + 1), None);
+    assert_eq!(s.char_at(usize::MAX), None);
+
+    // every char boundary agrees with `char_indices`
+    for (idx, ch) in s.char_indices() {
+        assert_eq!(s.char_at(idx), Some(ch));
+    }
+}
+
+#[test]
+fn test_char_at_heap() {
+    let text = "this string is long enough to be heap allocated: ñ";
+    let s = CompactString::from(text);
+    assert!(s.is_heap_allocated());
+
+    let idx = text.find('ñ').unwrap();
+    assert_eq!(s.char_at(idx), Some('ñ'));
+    assert_eq!(s.char_at(idx + 1), None);
+    assert_eq!(s.char_at(text.len()), None);
+}
+
+#[test_case("hello", &[0, 1, 2, 3, 4, 5]; "ascii")]
+#[test_case("h€llo 🦀", &[0, 1, 4, 5, 6, 7, 8, 12]; "multibyte")]
+#[test_case("", &[0]; "empty")]
+fn test_char_boundaries(text: &'static str, expected: &[usize]) {
+    for compact in [CompactString::new(text), CompactString::from_static_str(text)] {
+        let boundaries: Vec<usize> = compact.char_boundaries().collect();
+        assert_eq!(boundaries, expected);
+    }
+}
+
+#[test]
+fn test_char_boundaries_match_is_char_boundary() {
+    let compact = CompactString::new("a long string, with ümlauts and 🦀s, that is heap allocated");
+    let expected: Vec<usize> = (0..=compact.len())
+        .filter(|idx| compact.is_char_boundary(*idx))
+        .collect();
+
+    assert_eq!(compact.char_boundaries().collect::<Vec<_>>(), expected);
+}
+
+#[test]
+fn test_inline_limit_is_the_same_for_ascii() {
+    // the inline capacity is measured in bytes, ASCII strings don't get packed any tighter
+    let ascii = "a".repeat(MAX_SIZE);
+    let non_ascii = "ñ".repeat(MAX_SIZE / 2);
+    assert_eq!(ascii.len(), non_ascii.len());
+
+    assert!(!CompactString::new(&ascii).is_heap_allocated());
+    assert!(!CompactString::new(&non_ascii).is_heap_allocated());
+
+    let ascii = "a".repeat(MAX_SIZE + 1);
+    let non_ascii = "ñ".repeat(MAX_SIZE / 2 + 1);
+    assert!(CompactString::new(&ascii).is_heap_allocated());
+    assert!(CompactString::new(&non_ascii).is_heap_allocated());
+}
+
+#[test]
+fn test_from_fn() {
+    // a fixed sequence
+    let mut chars = "hello world".chars();
+    let compact = CompactString::from_fn(|| chars.next());
+    assert_eq!(compact, "hello world");
+    assert!(!compact.is_heap_allocated());
+
+    // an empty sequence
+    let compact = CompactString::from_fn(|| None);
+    assert_eq!(compact, "");
+    assert!(!compact.is_heap_allocated());
+
+    // mixed ASCII and multi-byte chars, long enough to spill onto the heap
+    let mut count = 0;
+    let compact = CompactString::from_fn(|| {
+        count += 1;
+        match count {
+            1..=10 => Some('a'),
+            11..=20 => Some('🦀'),
+            21..=30 => Some('ü'),
+            _ => None,
+        }
+    });
+    let expected = format!("{}{}{}", "a".repeat(10), "🦀".repeat(10), "ü".repeat(10));
+    assert_eq!(compact, expected);
+    assert!(compact.is_heap_allocated());
+    // the closure isn't called again after returning `None`
+    assert_eq!(count, 31);
+}
+
+#[test]
+fn test_comparisons_ignore_spare_capacity() {
+    use core::cmp::Ordering;
+    use core::hash::{
+        Hash,
+        Hasher,
+    };
+    use std::collections::hash_map::DefaultHasher;
+
+    fn hash(value: &impl Hash) -> u64 {
+        let mut hasher = DefaultHasher::new();
+        value.hash(&mut hasher);
+        hasher.finish()
+    }
+
+    // the tail of the heap buffer is never written to, so Miri will catch any reads of it
+    let mut spare = CompactString::with_capacity(1024);
+    spare.push_str("this string has a lot of spare capacity");
+    assert!(spare.is_heap_allocated());
+    assert!(spare.capacity() >= 1024);
+
+    let tight = CompactString::new("this string has a lot of spare capacity");
+    assert!(tight.capacity() < spare.capacity());
+
+    assert_eq!(spare, tight);
+    assert_eq!(spare.cmp(&tight), Ordering::Equal);
+    assert_eq!(hash(&spare), hash(&tight));
+    assert_eq!(spare.as_bytes(), tight.as_bytes());
+    assert_eq!(spare, "this string has a lot of spare capacity");
+
+    // removing a char leaves stale bytes behind the new length, which must be ignored as well
+    spare.pop();
+    assert_ne!(spare, tight);
+    assert_eq!(spare.cmp(&tight), Ordering::Less);
+    assert_eq!(spare, "this string has a lot of spare capacit");
+    assert_eq!(spare.as_bytes().len(), spare.len());
+}
+
+#[test_case("hello    world", "hello world" ; "multiple spaces")]
+#[test_case("hello\t\n world\r\nfoo", "hello world foo" ; "tabs and newlines")]
+#[test_case("  \t hello world \n", "hello world" ; "leading and trailing")]
+#[test_case("hello\u{3000}\u{a0}world", "hello world" ; "unicode whitespace")]
+#[test_case("hello world", "hello world" ; "already collapsed")]
+#[test_case(" \t\n\r ", "" ; "only whitespace")]
+#[test_case("", "" ; "empty")]
+fn test_collapse_whitespace(text: &'static str, expected: &str) {
+    let collapsed = CompactString::new(text).collapse_whitespace();
+    assert_eq!(collapsed, expected);
+    assert!(!collapsed.is_heap_allocated());
+
+    let collapsed = CompactString::from_static_str(text).collapse_whitespace();
+    assert_eq!(collapsed, expected);
+}
+
+#[test]
+fn test_collapse_whitespace_heap() {
+    let text = "   this   string\tis\n\nlong enough   to be heap allocated   ";
+    let compact = CompactString::new(text);
+    assert!(compact.is_heap_allocated());
+
+    let collapsed = compact.collapse_whitespace();
+    assert_eq!(collapsed, "this string is long enough to be heap allocated");
+    assert_eq!(compact, text);
+}
+
+#[test_case(CompactString::from; "inline")]
+#[test_case(CompactString::from_static_str; "static_str")]
+fn test_split_once(to_compact: fn(&'static str) -> CompactString) {
+    // present delimiter
+    let pair = to_compact("key=value");
+    assert_eq!(pair.split_once('='), Some(("key", "value")));
+    assert_eq!(pair.rsplit_once("="), Some(("key", "value")));
+
+    // absent delimiter
+    assert_eq!(pair.split_once(':'), None);
+    assert_eq!(pair.rsplit_once(':'), None);
+
+    // delimiter at the start and the end
+    let start = to_compact("=value");
+    assert_eq!(start.split_once('='), Some(("", "value")));
+    let end = to_compact("key=");
+    assert_eq!(end.split_once('='), Some(("key", "")));
+
+    // `split_once` picks the first delimiter, `rsplit_once` the last
+    let nested = to_compact("a=b=c");
+    assert_eq!(nested.split_once('='), Some(("a", "b=c")));
+    assert_eq!(nested.rsplit_once('='), Some(("a=b", "c")));
+}
+
+#[test]
+fn test_split_once_heap() {
+    let compact = CompactString::from("a_long_configuration_key = 🦀 a long configuration value");
+    assert!(compact.is_heap_allocated());
+
+    let (key, value) = compact.split_once(" = ").unwrap();
+    assert_eq!(key, "a_long_configuration_key");
+    assert_eq!(value, "🦀 a long configuration value");
+    let (rest, last) = compact.rsplit_once(' ').unwrap();
+    assert_eq!(rest, "a_long_configuration_key = 🦀 a long configuration");
+    assert_eq!(last, "value");
+}
+
+#[test]
+fn test_parse() {
+    let int = CompactString::new("4096");
+    assert_eq!(int.parse::<u32>(), Ok(4096));
+    assert_eq!(int.parse::<i64>(), Ok(4096));
+
+    let float = CompactString::new("-1.5e3");
+    assert_eq!(float.parse::<f64>(), Ok(-1500.0));
+
+    let invalid = CompactString::new("4096 apples");
+    let err: num::ParseIntError = invalid.parse::<u32>().unwrap_err();
+    assert_eq!(err, "x".parse::<u32>().unwrap_err());
+    assert!(invalid.parse::<f32>().is_err());
+
+    let overflow = CompactString::new("256");
+    assert_eq!(overflow.parse::<u8>(), "256".parse::<u8>());
+
+    // parsing into another CompactString
+    let heap = CompactString::new("a long string that will be allocated on the heap");
+    assert_eq!(heap.parse::<CompactString>(), Ok(heap.clone()));
+}
+
+#[test_case("hello" ; "inline")]
+#[test_case("a string that is long enough to be heap allocated" ; "heap")]
+fn test_as_mut_str_in_place(text: &str) {
+    let mut compact = CompactString::with_capacity(100);
+    compact.push_str(text);
+    let ptr = compact.as_ptr();
+
+    let s = compact.as_mut_str();
+    // only the live bytes are returned, not the whole capacity
+    assert_eq!(s.len(), text.len());
+    s.make_ascii_uppercase();
+
+    assert_eq!(compact, text.to_ascii_uppercase());
+    assert_eq!(compact.as_ptr(), ptr);
+    assert!(compact.capacity() >= 100);
+}
+
+#[test_case("hello" ; "inline")]
+#[test_case("a &'static str that is long enough to be heap allocated" ; "heap")]
+fn test_as_mut_str_static_str_is_copied(text: &'static str) {
+    let mut compact = CompactString::from_static_str(text);
+    let copy = compact.clone();
+
+    let s = compact.as_mut_str();
+    assert_eq!(s.len(), text.len());
+    assert_ne!(s.as_ptr(), text.as_ptr());
+    s.make_ascii_uppercase();
+
+    assert_eq!(compact, text.to_ascii_uppercase());
+    assert_eq!(compact.as_static_str(), None);
+    // the original `&'static str` and other copies of it are unchanged
+    assert_eq!(copy, text);
+}
+
+#[test]
+fn test_split_at_mut() {
+    let mut compact = CompactString::new("a string that is long enough, for the heap");
+    let ptr = compact.as_ptr();
+
+    // `split_at_mut` comes from `str` via `DerefMut`, the halves are disjoint so they can be
+    // mutated in parallel
+    let (left, right) = compact.split_at_mut(28);
+    rayon::join(|| left.make_ascii_uppercase(), || right.make_ascii_uppercase());
+
+    assert_eq!(compact, "A STRING THAT IS LONG ENOUGH, FOR THE HEAP");
+    // we mutated our buffer in place
+    assert_eq!(compact.as_ptr(), ptr);
+}
+
+#[test]
+fn test_split_at_mut_static_str_is_copied() {
+    static TEXT: &str = "a &'static str that is long enough to be heap allocated";
+
+    let mut compact = CompactString::from_static_str(TEXT);
+    assert!(compact.as_static_str().is_some());
+
+    let (left, right) = compact.split_at_mut(2);
+    assert_eq!(left, "a ");
+    left.make_ascii_uppercase();
+    right[..9].make_ascii_uppercase();
+
+    // the `&'static str` gets copied before we return mutable references to it
+    assert_eq!(compact, "A &'STATIC str that is long enough to be heap allocated");
+    assert!(compact.as_static_str().is_none());
+    assert_eq!(TEXT, "a &'static str that is long enough to be heap allocated");
+}
+
+#[test]
+#[should_panic]
+fn test_split_at_mut_not_char_boundary() {
+    let mut compact = CompactString::new("🦀");
+    let _ = compact.split_at_mut(1);
+}
+
+#[test_case("Hello, World!", "helloworld" ; "inline")]
+#[test_case("A Heap Allocated String, Too Long", "aheapallocatedstringtoolong" ; "heap")]
+#[test_case("ÀÉÎ, ÕÜ!", "àéîõü" ; "multi byte")]
+#[test_case("", "" ; "empty")]
+fn test_retain_mut_lowercase(text: &'static str, expected: &str) {
+    let mut compact = CompactString::new(text);
+    compact.retain_mut(|c| {
+        *c = c.to_lowercase().next().unwrap();
+        c.is_alphabetic()
+    });
+    assert_eq!(compact, expected);
+
+    let mut compact = CompactString::from_static_str(text);
+    compact.retain_mut(|c| {
+        *c = c.to_lowercase().next().unwrap();
+        c.is_alphabetic()
+    });
+    assert_eq!(compact, expected);
+}
+
+#[test]
+fn test_retain_mut_drops_all() {
+    let mut compact = CompactString::new("a string that is long enough to be heap allocated");
+    compact.retain_mut(|_| false);
+    assert_eq!(compact, "");
+}
+
+#[test]
+fn test_retain_mut_shrinks_chars() {
+    let mut compact = CompactString::new("🦀 crabs 🦀 and ferris 🦀");
+    let ptr = compact.as_ptr();
+    compact.retain_mut(|c| {
+        if *c == '🦀' {
+            *c = 'c';
+        }
+        *c != ' '
+    });
+    assert_eq!(compact, "ccrabscandferrisc");
+    // we updated the string in place
+    assert_eq!(compact.as_ptr(), ptr);
+}
+
+#[test_case("abc", "🦀🦀🦀" ; "all grow")]
+#[test_case("a-b-c", "🦀🦀🦀" ; "grow and drop")]
+#[test_case("---abc", "🦀🦀🦀" ; "room to grow")]
+#[test_case("ab-c--", "🦀🦀🦀" ; "drop after grow")]
+#[test_case("a heap allocated string, long enough", "🦀 he🦀p 🦀llo🦀🦀ted string, long enough" ; "heap")]
+fn test_retain_mut_grows_chars(text: &str, expected: &str) {
+    let mut compact = CompactString::new(text);
+    let mut count = 0;
+    compact.retain_mut(|c| {
+        count += 1;
+        if *c == '-' {
+            return false;
+        }
+        if *c == 'a' || *c == 'b' || *c == 'c' {
+            *c = '🦀';
+        }
+        true
+    });
+    assert_eq!(compact, expected);
+    // the predicate is called exactly once for each char
+    assert_eq!(count, text.chars().count());
+}
+
+#[test_case("éa🦀b", "a" ; "inline")]
+#[test_case("é and a string that is long enough to be heap allocated 🦀", " and a string that is long enough to be heap allocated " ; "heap")]
+fn test_retain_panic_leaves_valid_utf8(text: &'static str, expected: &str) {
+    // removing the 'é' moves the chars after it, so we'd leave half of it behind if the string
+    // wasn't truncated to the chars we kept
+    let mut compact = CompactString::new(text);
+    let result = std::panic::catch_unwind(std::panic::AssertUnwindSafe(|| {
+        compact.retain(|c| match c {
+            'é' => false,
+            '🦀' => panic!("crab"),
+            _ => true,
+        });
+    }));
+    assert!(result.is_err());
+    assert!(core::str::from_utf8(compact.as_bytes()).is_ok());
+    assert_eq!(compact, expected);
+
+    let mut compact = CompactString::new(text);
+    let result = std::panic::catch_unwind(std::panic::AssertUnwindSafe(|| {
+        compact.retain_mut(|c| match *c {
+            'é' => false,
+            '🦀' => panic!("crab"),
+            _ => true,
+        });
+    }));
+    assert!(result.is_err());
+    assert!(core::str::from_utf8(compact.as_bytes()).is_ok());
+    assert_eq!(compact, expected);
+}
+
+#[test]
+fn test_retain_mut_panic_after_grow() {
+    // growing the 'a' collects the rest into a new buffer, which we never get to use
+    let mut compact = CompactString::new("a-🦀");
+    let result = std::panic::catch_unwind(std::panic::AssertUnwindSafe(|| {
+        compact.retain_mut(|c| match *c {
+            'a' => {
+                *c = '🦀';
+                true
+            }
+            '🦀' => panic!("crab"),
+            _ => true,
+        });
+    }));
+    assert!(result.is_err());
+    assert!(core::str::from_utf8(compact.as_bytes()).is_ok());
+    assert_eq!(compact, "");
+}
+
+#[test]
+fn test_heap_size() {
+    assert_eq!(CompactString::new("").heap_size(), 0);
+    assert_eq!(CompactString::new("hello world").heap_size(), 0);
+    assert_eq!(CompactString::with_capacity(MAX_SIZE).heap_size(), 0);
+
+    let text = "a &'static str that is too long to be inlined";
+    let compact = CompactString::from_static_str(text);
+    assert_eq!(compact.heap_size(), 0);
+
+    // on 64-bit arches the capacity is always stored inline, so the allocation is exactly our
+    // capacity, on 32-bit arches the capacity only gets stored on the heap for huge strings
+    let mut compact = CompactString::new(text);
+    assert!(compact.is_heap_allocated());
+    assert_eq!(compact.heap_size(), compact.capacity());
+
+    compact.reserve(100);
+    assert_eq!(compact.heap_size(), compact.capacity());
+
+    compact.clear();
+    compact.shrink_to_fit();
+    assert!(!compact.is_heap_allocated());
+    assert_eq!(compact.heap_size(), 0);
+}
+
+#[test]
+fn test_assert_inline() {
+    CompactString::new("").assert_inline();
+    CompactString::new("hello world").assert_inline();
+    CompactString::from_static_str("a &'static str that is too long to be inlined").assert_inline();
+
+    // shrinking a heap allocated string back to its inline size passes again
+    let mut compact = CompactString::new("a string that is too long to be inlined");
+    compact.truncate(5);
+    compact.shrink_to_fit();
+    compact.assert_inline();
+}
+
+#[test]
+#[cfg(debug_assertions)]
+#[should_panic(expected = "expected CompactString to be inline")]
+fn test_assert_inline_heap_allocated() {
+    CompactString::new("a string that is too long to be inlined").assert_inline();
+}
+
+#[test_case("foo foo 123 foo", "foo", "new", 1, "new foo 123 foo" ; "first")]
+#[test_case("foo foo 123 foo", "foo", "new", 2, "new new 123 foo" ; "first two")]
+#[test_case("foo foo 123 foo", "foo", "new", 10, "new new 123 new" ; "count larger")]
+#[test_case("foo foo 123 foo", "foo", "new", 0, "foo foo 123 foo" ; "count zero")]
+#[test_case("foo foo 123 foo", "bar", "new", 1, "foo foo 123 foo" ; "not present")]
+#[test_case("foo foo 123 foo", "foo ", "", 2, "123 foo" ; "remove")]
+#[test_case("🦀🦀", "🦀", "a heap allocated crab, ", 1, "a heap allocated crab, 🦀" ; "grow")]
+#[test_case("abc", "", "-", 10, "-a-b-c-" ; "empty pattern")]
+#[test_case("", "foo", "new", 1, "" ; "empty")]
+fn test_replacen(text: &str, from: &str, to: &str, count: usize, expected: &str) {
+    let compact = CompactString::new(text);
+    let replaced = compact.replacen(from, to, count);
+
+    assert_eq!(replaced, expected);
+    assert_eq!(replaced, text.replacen(from, to, count));
+    assert_eq!(replaced.is_heap_allocated(), expected.len() > MAX_SIZE);
+}
+
+#[test_case("42", 5, '0', "00042", "42000" ; "shorter")]
+#[test_case("hello", 5, '0', "hello", "hello" ; "equal")]
+#[test_case("hello world", 5, '0', "hello world", "hello world" ; "longer")]
+#[test_case("", 3, '-', "---", "---" ; "empty")]
+#[test_case("név", 5, '🦀', "🦀🦀név", "név🦀🦀" ; "multi byte fill")]
+#[test_case("id", 30, ' ', "                            id", "id                            " ; "heap")]
+fn test_pad(text: &str, width: usize, fill: char, start: &str, end: &str) {
+    let compact = CompactString::new(text);
+
+    let padded = compact.pad_start(width, fill);
+    assert_eq!(padded, start);
+    assert_eq!(padded.is_heap_allocated(), start.len() > MAX_SIZE);
+
+    let padded = compact.pad_end(width, fill);
+    assert_eq!(padded, end);
+    assert_eq!(padded.is_heap_allocated(), end.len() > MAX_SIZE);
+}
+
+#[test_case("a\nb\nc\n", &["a", "b", "c"] ; "trailing terminator")]
+#[test_case("a\nb\nc", &["a", "b", "c"] ; "no trailing terminator")]
+#[test_case("a\n\nb\n\n", &["a", "", "b", ""] ; "consecutive terminators")]
+#[test_case("\n", &[""] ; "only terminator")]
+#[test_case("", &[] ; "empty")]
+#[test_case("a record that is long enough to be heap allocated\nü🦀\n", &[
+    "a record that is long enough to be heap allocated",
+    "ü🦀",
+] ; "heap")]
+fn test_split_terminator(text: &'static str, expected: &[&str]) {
+    // `split_terminator` and `rsplit_terminator` come from `str` via `Deref`
+    for compact in [CompactString::new(text), CompactString::from_static_str(text)] {
+        let records: Vec<&str> = compact.split_terminator('\n').collect();
+        assert_eq!(records, expected);
+
+        let mut records: Vec<&str> = compact.rsplit_terminator("\n").collect();
+        records.reverse();
+        assert_eq!(records, expected);
+    }
+}
+
+#[test_case("hello world", true ; "ascii")]
+#[test_case("a long ascii string, that is heap allocated", true ; "ascii heap")]
+#[test_case("hello wörld", false ; "one multi byte char")]
+#[test_case("a long string with a single multi byte char 🦀", false ; "multi byte heap")]
+#[test_case("\x7F", true ; "max ascii")]
+#[test_case("", true ; "empty")]
+fn test_is_ascii(text: &'static str, expected: bool) {
+    // `is_ascii` comes from `str` via `Deref`, which uses the optimized `[u8]::is_ascii`
+    assert_eq!(CompactString::new(text).is_ascii(), expected);
+    assert_eq!(CompactString::from_static_str(text).is_ascii(), expected);
+    assert_eq!(CompactString::new(text).as_bytes().is_ascii(), expected);
+}
+
+#[test]
+fn test_as_str_valid_after_mutations() {
+    // `as_str` debug asserts that we contain valid UTF-8, so each step checks the previous
+    // mutation upheld that invariant
+    let mut compact = CompactString::new("hello");
+
+    unsafe {
+        let buf = compact.as_mut_bytes();
+        buf[5..9].copy_from_slice("🦀".as_bytes());
+        compact.set_len(9);
+    }
+    assert_eq!(compact.as_str(), "hello🦀");
+
+    compact.insert_str(5, " world, this is long enough for the heap ");
+    assert_eq!(compact.as_str(), "hello world, this is long enough for the heap 🦀");
+
+    compact.as_mut_str().make_ascii_uppercase();
+    compact.replace_range(..5, "HÉLLO");
+    assert_eq!(compact.remove(compact.len() - 4), '🦀');
+    compact.truncate(12);
+    compact.push('ß');
+    assert_eq!(compact.as_str(), "HÉLLO WORLDß");
+
+    compact.retain(|c| c.is_ascii());
+    compact.shrink_to_fit();
+    assert_eq!(compact.as_str(), "HLLO WORLD");
+}
+
+#[test]
+#[cfg(debug_assertions)]
+#[should_panic(expected = "invalid UTF-8")]
+fn test_as_str_invalid_utf8_panics() {
+    let mut compact = CompactString::new("hello");
+    // violate the contract of `as_mut_bytes` by writing invalid UTF-8
+    unsafe { compact.as_mut_bytes()[0] = 0xFF };
+    let _ = compact.as_str();
+}
+
+#[test_case(CompactString::new(""); "empty")]
+#[test_case(CompactString::new("hello"); "inline")]
+#[test_case(CompactString::from_static_str("hello"); "short static_str")]
+#[test_case(CompactString::from(String::from("hello")); "from string")]
+#[test_case(CompactString::with_capacity(MAX_SIZE); "with_capacity")]
+fn test_inline_capacity(mut compact: CompactString) {
+    assert!(!compact.is_heap_allocated());
+    assert_eq!(compact.capacity(), MAX_SIZE);
+
+    // pushing up to the inline capacity stays inline
+    while compact.len() < MAX_SIZE {
+        compact.push('a');
+        assert!(!compact.is_heap_allocated());
+        assert_eq!(compact.capacity(), MAX_SIZE);
+    }
+
+    // and the next byte spills onto the heap
+    compact.push('a');
+    assert!(compact.is_heap_allocated());
+    assert!(compact.capacity() > MAX_SIZE);
+}
+
+#[test_case(CompactString::from; "inline")]
+#[test_case(CompactString::from_static_str; "static_str")]
+fn test_byte(to_compact: fn(&'static str) -> CompactString) {
+    let compact = to_compact("a🦀z");
+
+    assert_eq!(compact.byte(0), Some(b'a'));
+    assert_eq!(compact.byte(1), Some(0xF0));
+    assert_eq!(compact.byte(5), Some(b'z'));
+    for (idx, byte) in compact.bytes().enumerate() {
+        assert_eq!(compact.byte(idx), Some(byte));
+    }
+
+    assert_eq!(compact.byte(6), None);
+    assert_eq!(compact.byte(usize::MAX), None);
+    assert_eq!(to_compact("").byte(0), None);
+}
+
+#[test]
+fn test_byte_ignores_spare_capacity() {
+    let mut compact = CompactString::with_capacity(128);
+    compact.push_str("a string with plenty of spare capacity");
+    assert!(compact.capacity() > compact.len());
+
+    assert_eq!(compact.byte(compact.len() - 1), Some(b'y'));
+    assert_eq!(compact.byte(compact.len()), None);
+}
+
+#[test]
+fn test_copy_to_array() {
+    // fits, with room to spare
+    let compact = CompactString::new("héllo");
+    let (buf, len) = compact.copy_to_array::<16>().unwrap();
+    assert_eq!(len, 6);
+    assert_eq!(&buf[..len], "héllo".as_bytes());
+    assert!(buf[len..].iter().all(|b| *b == 0));
+
+    // exactly fills the array
+    let (buf, len) = compact.copy_to_array::<6>().unwrap();
+    assert_eq!(len, 6);
+    assert_eq!(&buf, "héllo".as_bytes());
+
+    // too long
+    assert_eq!(compact.copy_to_array::<5>(), None);
+    assert_eq!(compact.copy_to_array::<0>(), None);
+
+    // empty strings always fit
+    assert_eq!(CompactString::new("").copy_to_array::<0>(), Some(([], 0)));
+
+    // heap allocated strings can be copied too
+    let compact = CompactString::new("a string long enough to be heap allocated");
+    let (buf, len) = compact.copy_to_array::<64>().unwrap();
+    assert_eq!(&buf[..len], compact.as_bytes());
+    assert_eq!(compact.copy_to_array::<32>(), None);
+}
+
+#[test]
+fn test_repeat_with_sep() {
+    let placeholder = CompactString::new("?");
+
+    // n == 0
+    assert_eq!(placeholder.repeat_with_sep(0, ","), "");
+    // n == 1, no separator
+    assert_eq!(placeholder.repeat_with_sep(1, ","), "?");
+    // several repeats
+    assert_eq!(placeholder.repeat_with_sep(3, ","), "?,?,?");
+    assert_eq!(placeholder.repeat_with_sep(4, ", "), "?, ?, ?, ?");
+    assert_eq!(CompactString::new("🦀").repeat_with_sep(3, "—"), "🦀—🦀—🦀");
+
+    // empty string or separator
+    assert_eq!(CompactString::new("").repeat_with_sep(3, ","), ",,");
+    assert_eq!(placeholder.repeat_with_sep(3, ""), "???");
+    assert_eq!(CompactString::new("").repeat_with_sep(usize::MAX, ""), "");
+
+    // exactly one allocation, of the right size
+    let long = placeholder.repeat_with_sep(100, ", ");
+    assert_eq!(long.len(), 100 + 99 * 2);
+    assert_eq!(long.capacity(), long.len());
+    assert_eq!(long, vec!["?"; 100].join(", "));
+
+    // short results stay inline
+    assert!(!placeholder.repeat_with_sep(8, ",").is_heap_allocated());
+}
+
+#[test]
+#[should_panic(expected = "Cannot allocate memory to hold CompactString")]
+fn test_repeat_with_sep_overflow() {
+    let _ = CompactString::new("?").repeat_with_sep(usize::MAX, ",");
+}
+
+#[test]
+#[should_panic(expected = "Cannot allocate memory to hold CompactString")]
+fn test_repeat_with_sep_separator_overflow() {
+    let _ = CompactString::new("").repeat_with_sep(usize::MAX / 2 + 2, "ab");
+}
+
+#[test]
+fn test_hash_matches_str() {
+    use core::hash::{
+        BuildHasher,
+        Hash,
+        Hasher,
+    };
+    use std::collections::hash_map::RandomState;
+    use std::collections::HashMap;
+
+    // `BuildHasher::hash_one` needs Rust 1.71, above our MSRV
+    #[allow(clippy::manual_hash_one)]
+    fn hash(state: &RandomState, value: &(impl Hash + ?Sized)) -> u64 {
+        let mut hasher = state.build_hasher();
+        value.hash(&mut hasher);
+        hasher.finish()
+    }
+
+    let state = RandomState::new();
+    for s in ["", "a", "hello world", "this string is long enough to be heap allocated", "🦀"] {
+        let compact = CompactString::new(s);
+        assert_eq!(hash(&state, &compact), hash(&state, s));
+        assert_eq!(hash(&state, &compact), hash(&state, &String::from(s)));
+    }
+
+    // hashing the bytes directly omits the terminator `str` writes, and would not match
+    let compact = CompactString::new("ab");
+    assert_ne!(hash(&state, &compact), hash(&state, compact.as_bytes()));
+
+    // lookups through `Borrow<str>` depend on the hashes matching
+    let mut map = HashMap::new();
+    map.insert(CompactString::new("short"), 1);
+    map.insert(CompactString::new("a much longer key that lives on the heap"), 2);
+    assert_eq!(map.get("short"), Some(&1));
+    assert_eq!(map.get("a much longer key that lives on the heap"), Some(&2));
+    assert_eq!(map.get("missing"), None);
+}
+
+#[test_case(CompactString::new("hello world"); "inline")]
+#[test_case(CompactString::new("a string long enough to be heap allocated"); "heap")]
+#[test_case(CompactString::from_static_str("static strs share their buffer"); "static")]
+fn test_eq_after_truncating_clone(original: CompactString) {
+    let text = original.to_string();
+    let mut truncated = original.clone();
+    truncated.truncate(5);
+    if let Some(s) = original.as_static_str() {
+        assert_eq!(truncated.as_ptr(), s.as_ptr());
+    }
+
+    // each string only reads its own `len` bytes, even if they share a buffer
+    assert_ne!(original, truncated);
+    assert_eq!(original, text);
+    assert_eq!(truncated, &text[..5]);
+    assert_eq!(original.as_bytes(), text.as_bytes());
+    assert_eq!(truncated.as_bytes(), &text.as_bytes()[..5]);
+    assert!(truncated < original);
+
+    // and comparing the other way around gives the same answer
+    assert_ne!(truncated, original);
+    assert!(original > truncated);
+}
+
+#[test]
+fn test_concat_in_place() {
+    let mut hello = CompactString::with_capacity(64);
+    hello.push_str("hello ");
+    let ptr = hello.as_ptr();
+    let capacity = hello.capacity();
+
+    let hello_world = hello.concat(CompactString::new("world"));
+    assert_eq!(hello_world, "hello world");
+    assert_eq!(hello_world.as_ptr(), ptr);
+    assert_eq!(hello_world.capacity(), capacity);
+
+    // short strings that fit inline stay inline
+    let short = CompactString::new("abc").concat(CompactString::new("def"));
+    assert_eq!(short, "abcdef");
+    assert!(!short.is_heap_allocated());
+}
+
+#[test]
+fn test_concat_reuses_other() {
+    // `self` has no spare capacity, but `other` does
+    let prefix = CompactString::new("a prefix that is too long to be inlined, ");
+    assert_eq!(prefix.capacity(), prefix.len());
+
+    let mut other = CompactString::with_capacity(128);
+    other.push_str("and a suffix");
+    let ptr = other.as_ptr();
+
+    let joined = prefix.concat(other);
+    assert_eq!(joined, "a prefix that is too long to be inlined, and a suffix");
+    assert_eq!(joined.as_ptr(), ptr);
+}
+
+#[test]
+fn test_concat_reallocates() {
+    let a = CompactString::new("neither of these two heap allocated strings has ");
+    let b = CompactString::new("any spare capacity left over at the end");
+    assert_eq!(a.capacity(), a.len());
+    assert_eq!(b.capacity(), b.len());
+
+    let joined = a.concat(b);
+    assert_eq!(
+        joined,
+        "neither of these two heap allocated strings has any spare capacity left over at the end"
+    );
+    assert_eq!(joined.capacity(), joined.len());
+}
+
+#[test]
+fn test_concat_static_str() {
+    const TEXT: &str = "a static str that is shared between clones";
+    let original = CompactString::from_static_str(TEXT);
+
+    // the shared buffer is copied, never written to
+    let joined = original.clone().concat(CompactString::new("!"));
+    assert_eq!(joined, "a static str that is shared between clones!");
+    assert_eq!(original, TEXT);
+    assert_eq!(original.as_static_str(), Some(TEXT));
+
+    let joined = CompactString::new("> ").concat(original.clone());
+    assert_eq!(joined, "> a static str that is shared between clones");
+    assert_eq!(original.as_static_str(), Some(TEXT));
+
+    // even when there is nothing to append
+    let joined = original.clone().concat(CompactString::new(""));
+    assert_eq!(joined, TEXT);
+    assert_eq!(original.as_static_str(), Some(TEXT));
+}
+
+#[test_case(0 ; "zero")]
+#[test_case(MAX_SIZE ; "max inline")]
+#[test_case(MAX_SIZE + 1 ; "min heap")]
+#[test_case(100 ; "heap")]
+#[test_case(4097 ; "large")]
+fn test_push_within_capacity_does_not_reallocate(requested: usize) {
+    let mut compact = CompactString::with_capacity(requested);
+    let capacity = compact.capacity();
+    assert!(capacity >= requested);
+
+    // we can use all of the capacity we report, not only what was requested
+    let ptr = compact.as_ptr();
+    while compact.len() < capacity {
+        compact.push('a');
+    }
+    assert_eq!(compact.capacity(), capacity);
+    assert_eq!(compact.as_ptr(), ptr);
+}
+
+#[test]
+fn test_reserve_within_spare_capacity() {
+    let mut compact = CompactString::new("a string that is long enough to be heap allocated");
+    compact.push('!');
+    let capacity = compact.capacity();
+    let ptr = compact.as_ptr();
+    let spare = capacity - compact.len();
+    assert!(spare > 0);
+
+    // after the first growth, reserving anything that fits in the spare capacity is a no-op
+    for additional in [0, 1, spare] {
+        compact.reserve(additional);
+        assert_eq!(compact.capacity(), capacity);
+        assert_eq!(compact.as_ptr(), ptr);
+    }
+    while compact.len() < capacity {
+        compact.push('a');
+        assert_eq!(compact.as_ptr(), ptr);
+    }
+    assert_eq!(compact.capacity(), capacity);
+
+    // only when the spare capacity runs out do we grow
+    compact.reserve(1);
+    assert!(compact.capacity() > capacity);
+
+    // huge reserves still get checked for overflow
+    assert!(compact.try_reserve(usize::MAX).is_err());
+
+    // a `&'static str` has no spare capacity, and even reserving nothing gives us our own copy
+    let text = "a &'static str that is long enough to be heap allocated";
+    let mut compact = CompactString::from_static_str(text);
+    compact.reserve(0);
+    assert_eq!(compact, text);
+    assert_eq!(compact.as_static_str(), None);
+    assert_ne!(compact.as_ptr(), text.as_ptr());
+}
+
+#[test]
+#[cfg_attr(miri, ignore)]
+fn test_push_growth_factor() {
+    // the number of allocations this makes is checked in `tests/allocations.rs`
+    let mut compact = CompactString::default();
+
+    for ch in ['a', 'é', '🦀'].iter().copied().cycle().take(100_000) {
+        compact.push(ch);
+
+        // growing on the heap never over-allocates by more than our growth factor
+        if compact.is_heap_allocated() {
+            assert!(compact.capacity() <= compact.len() * 3 / 2 + 4);
+        }
+    }
+    assert_eq!(compact.chars().count(), 100_000);
+}
+
+#[test_case("Grüße, Jürgen ❤", 5, "Grüße"; "multibyte")]
+#[test_case("🦀🦀🦀 and a long string on the heap", 2, "🦀🦀"; "heap")]
+#[test_case("hello", 0, ""; "zero")]
+#[test_case("hello", 5, "hello"; "exact")]
+#[test_case("hello", 100, "hello"; "larger")]
+#[test_case("", 3, ""; "empty")]
+fn test_truncate_chars(text: &'static str, char_count: usize, expected: &str) {
+    let mut compact = CompactString::new(text);
+    let capacity = compact.capacity();
+    compact.truncate_chars(char_count);
+    assert_eq!(compact, expected);
+    assert_eq!(compact.chars().count(), expected.chars().count());
+    assert_eq!(compact.capacity(), capacity);
+
+    let mut compact = CompactString::from_static_str(text);
+    compact.truncate_chars(char_count);
+    assert_eq!(compact, expected);
+}
+
+#[test]
+fn test_slicing_borrows() {
+    let text = "🦀 a large string that we slice without copying any of it 🦀".repeat(100);
+    let compact = CompactString::new(&text);
+
+    // slicing goes through `Deref<Target = str>`, and borrows from the existing buffer
+    let substr: &str = &compact[5..25];
+    assert_eq!(substr, &text[5..25]);
+    assert_eq!(substr.as_ptr(), compact.as_ptr().wrapping_add(5));
+
+    let tail = compact.get(compact.len() - 4..).unwrap();
+    assert_eq!(tail, "🦀");
+    assert_eq!(tail.as_ptr(), compact.as_ptr().wrapping_add(compact.len() - 4));
+
+    // slices that need to outlive the `CompactString` have to be copied
+    let owned = CompactString::new(substr);
+    drop(compact);
+    assert_eq!(owned, &text[5..25]);
+}
+
+macro_rules! test_from_num {
+    ($($name:ident: $t:ty),*) => {
+        $(
+            #[test]
+            fn $name() {
+                for n in [<$t>::MIN, <$t>::MIN / 2, 0, 1, 42, <$t>::MAX / 3, <$t>::MAX] {
+                    let compact = CompactString::from(n);
+                    assert_eq!(compact, n.to_string());
+                    // small numbers stay inline
+                    assert_eq!(compact.is_heap_allocated(), compact.len() > MAX_SIZE);
+                }
+            }
+        )*
+    };
+}
+
+test_from_num!(
+    test_from_u8: u8,
+    test_from_u16: u16,
+    test_from_u32: u32,
+    test_from_u64: u64,
+    test_from_u128: u128,
+    test_from_usize: usize,
+    test_from_i8: i8,
+    test_from_i16: i16,
+    test_from_i32: i32,
+    test_from_i64: i64,
+    test_from_i128: i128,
+    test_from_isize: isize
+);
+
+#[test_case(0.0; "zero")]
+#[test_case(1.5; "one and a half")]
+#[test_case(-42.25; "negative")]
+#[test_case(1e100; "large")]
+#[test_case(f64::NAN; "nan")]
+#[test_case(f64::INFINITY; "infinity")]
+fn test_from_float(n: f64) {
+    // floats are formatted with `ryu`, the same as `to_compact_string()`
+    let compact = CompactString::from(n);
+    assert_eq!(compact, n.to_compact_string());
+    assert!(!compact.is_heap_allocated());
+
+    let compact = CompactString::from(n as f32);
+    assert_eq!(compact, (n as f32).to_compact_string());
+    assert!(!compact.is_heap_allocated());
+}
+
+#[test]
+fn test_with_mut_bytes() {
+    // inline
+    let mut compact = CompactString::new("hello");
+    let len = compact.with_mut_bytes(|bytes| {
+        bytes.extend_from_slice(" world".as_bytes());
+        bytes.len()
+    });
+    assert_eq!(len, 11);
+    assert_eq!(compact, "hello world");
+    assert!(!compact.is_heap_allocated());
+
+    // heap allocated strings are moved into the `Vec` and back, without copying
+    let mut compact = CompactString::new("a string that is long enough to be heap allocated");
+    let ptr = compact.as_ptr();
+    compact.with_mut_bytes(|bytes| {
+        assert_eq!(bytes.as_ptr(), ptr);
+        bytes.retain(|b| *b != b' ');
+        bytes.reverse();
+    });
+    assert_eq!(compact, "detacollapaehebothguonegnolsitahtgnirtsa");
+    assert_eq!(compact.as_ptr(), ptr);
+
+    // multi-byte characters can be modified, as long as the result is valid UTF-8
+    let mut compact = CompactString::new("crab: 🦀");
+    compact.with_mut_bytes(|bytes| {
+        let crab = bytes.split_off(6);
+        bytes.splice(0..0, crab);
+    });
+    assert_eq!(compact, "🦀crab: ");
+}
+
+#[test]
+#[should_panic(expected = "with_mut_bytes left invalid UTF-8")]
+fn test_with_mut_bytes_invalid_utf8() {
+    let mut compact = CompactString::new("🦀");
+    compact.with_mut_bytes(|bytes| bytes.truncate(2));
+}
+
+#[test]
+fn test_with_mut_bytes_invalid_utf8_leaves_empty() {
+    let mut compact = CompactString::new("a string that is long enough to be heap allocated 🦀");
+    let result = std::panic::catch_unwind(std::panic::AssertUnwindSafe(|| {
+        compact.with_mut_bytes(|bytes| bytes.pop());
+    }));
+    assert!(result.is_err());
+    assert_eq!(compact, "");
+}
+
+#[test_case(CompactString::from; "inline")]
+#[test_case(CompactString::from_static_str; "static_str")]
+#[test_case(|s| CompactString::from(s.repeat(4)); "heap")]
+fn test_bytes_in_place(to_compact: fn(&'static str) -> CompactString) {
+    let text = "hello, wörld! 🦀";
+    let mut compact = to_compact(text);
+    let expected = compact.to_ascii_uppercase();
+
+    compact.bytes_in_place(|bytes| bytes.make_ascii_uppercase());
+    assert_eq!(compact, expected);
+    assert!(compact.starts_with("HELLO, WöRLD! 🦀"));
+    assert_eq!(compact.as_static_str(), None);
+}
+
+#[test]
+#[should_panic(expected = "bytes_in_place left invalid UTF-8")]
+fn test_bytes_in_place_invalid_utf8() {
+    let mut compact = CompactString::new("crab: 🦀");
+    // swap the first two bytes of the crab's encoding
+    compact.bytes_in_place(|bytes| bytes.swap(6, 7));
+}
+
+#[test]
+fn test_bytes_in_place_invalid_utf8_leaves_empty() {
+    let mut compact = CompactString::new("a string that is long enough to be heap allocated 🦀");
+    let result = std::panic::catch_unwind(std::panic::AssertUnwindSafe(|| {
+        compact.bytes_in_place(|bytes| bytes[bytes.len() - 1] = b'a');
+    }));
+    assert!(result.is_err());
+    assert_eq!(compact, "");
+}
@@ -265,6 +265,24 @@ mod tests {
         assert_eq!(c, "🍎🍊🍌");
     }
 
+    #[test]
+    fn test_concat_join_empty_does_not_allocate() {
+        let empties = vec![""; 1000];
+
+        let c = empties.concat_compact();
+        assert_eq!(c, "");
+        assert!(!c.is_heap_allocated());
+
+        let c = empties.join_compact("");
+        assert_eq!(c, "");
+        assert!(!c.is_heap_allocated());
+
+        let nothing: [&str; 0] = [];
+        let c = nothing.join_compact("a long separator that would need to be heap allocated");
+        assert_eq!(c, "");
+        assert!(!c.is_heap_allocated());
+    }
+
     #[proptest]
     #[cfg_attr(miri, ignore)]
     fn proptest_concat(items: Vec<String>) {
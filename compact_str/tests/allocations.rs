@@ -0,0 +1,209 @@
+//! Tests that count the calls made to the global allocator, or make allocations fail.
+//!
+//! These live in their own test binary, so the counting allocator doesn't affect any other tests.
+//! Tests run in parallel on multiple threads, so allocations are counted per thread.
+
+use std::alloc::{
+    GlobalAlloc,
+    Layout,
+    System,
+};
+use std::cell::Cell;
+
+use compact_str::CompactString;
+
+/// Forwards to the [`System`] allocator, counting every allocation and reallocation, unless
+/// allocations are set to fail
+struct CountingAllocator;
+
+thread_local! {
+    static ALLOCATIONS: Cell<usize> = const { Cell::new(0) };
+    static FAIL_ALLOCATIONS: Cell<bool> = const { Cell::new(false) };
+}
+
+fn record_allocation() {
+    // the thread local has no destructor, so this only fails while the thread is torn down
+    let _ = ALLOCATIONS.try_with(|count| count.set(count.get() + 1));
+}
+
+fn should_fail() -> bool {
+    FAIL_ALLOCATIONS.try_with(Cell::get).unwrap_or(false)
+}
+
+unsafe impl GlobalAlloc for CountingAllocator {
+    unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
+        record_allocation();
+        if should_fail() {
+            return core::ptr::null_mut();
+        }
+        System.alloc(layout)
+    }
+
+    unsafe fn dealloc(&self, ptr: *mut u8, layout: Layout) {
+        System.dealloc(ptr, layout)
+    }
+
+    unsafe fn realloc(&self, ptr: *mut u8, layout: Layout, new_size: usize) -> *mut u8 {
+        record_allocation();
+        if should_fail() {
+            return core::ptr::null_mut();
+        }
+        System.realloc(ptr, layout, new_size)
+    }
+}
+
+#[global_allocator]
+static GLOBAL: CountingAllocator = CountingAllocator;
+
+/// Runs `f`, returning its result and the number of (re)allocations it made on this thread
+fn count_allocations<R>(f: impl FnOnce() -> R) -> (R, usize) {
+    let before = ALLOCATIONS.with(Cell::get);
+    let result = f();
+    let after = ALLOCATIONS.with(Cell::get);
+    (result, after - before)
+}
+
+/// Runs `f` with every (re)allocation on this thread failing, like when we're out of memory
+///
+/// Note: `f` must not panic, since panicking allocates
+fn with_failing_allocations<R>(f: impl FnOnce() -> R) -> R {
+    FAIL_ALLOCATIONS.with(|fail| fail.set(true));
+    let result = f();
+    FAIL_ALLOCATIONS.with(|fail| fail.set(false));
+    result
+}
+
+#[test]
+#[cfg_attr(miri, ignore)]
+fn test_push_amortized_growth() {
+    let mut compact = CompactString::default();
+
+    let ((), allocations) = count_allocations(|| {
+        for ch in ['a', 'é', '🦀'].iter().copied().cycle().take(100_000) {
+            compact.push(ch);
+        }
+    });
+    assert_eq!(compact.chars().count(), 100_000);
+
+    // growing by 1.5x, we need a logarithmic number of (re)allocations
+    let max_allocations = (compact.len() as f64).log(1.5).ceil() as usize;
+    assert!(allocations > 0);
+    assert!(
+        allocations <= max_allocations,
+        "{} allocations for {} bytes",
+        allocations,
+        compact.len(),
+    );
+
+    // pushing within our capacity never allocates
+    compact.shrink_to_fit();
+    compact.reserve(100);
+    let ((), allocations) = count_allocations(|| {
+        for _ in 0..100 {
+            compact.push('a');
+        }
+    });
+    assert_eq!(allocations, 0);
+}
+
+#[test]
+fn test_failing_allocations_return_err() {
+    const LONG: &str = "a string that is long enough to be heap allocated";
+
+    let (new, with_capacity) = with_failing_allocations(|| {
+        (
+            CompactString::try_new(LONG),
+            CompactString::try_with_capacity(100),
+        )
+    });
+    assert!(new.is_err());
+    assert!(with_capacity.is_err());
+
+    // growing an inline string needs a new allocation
+    let mut inline = CompactString::new("short");
+    let (reserve, reserve_exact) =
+        with_failing_allocations(|| (inline.try_reserve(100), inline.try_reserve_exact(100)));
+    assert!(reserve.is_err());
+    assert!(reserve_exact.is_err());
+    assert_eq!(inline, "short");
+
+    // growing a heap allocated string goes through realloc, and then falls back to allocating
+    let mut heap = CompactString::new(LONG);
+    let (reserve, reserve_exact) =
+        with_failing_allocations(|| (heap.try_reserve(1000), heap.try_reserve_exact(1000)));
+    assert!(reserve.is_err());
+    assert!(reserve_exact.is_err());
+    assert_eq!(heap, LONG);
+
+    // a `&'static str` gets copied into an allocation before it can grow
+    let mut static_str = CompactString::from_static_str(LONG);
+    let reserve = with_failing_allocations(|| static_str.try_reserve(1));
+    assert!(reserve.is_err());
+    assert_eq!(static_str, LONG);
+    assert_eq!(static_str.as_static_str(), Some(LONG));
+
+    // nothing that fits inline needs to allocate at all
+    let (new, with_capacity, reserve) = with_failing_allocations(|| {
+        (
+            CompactString::try_new("short"),
+            CompactString::try_with_capacity(10),
+            inline.try_reserve(1),
+        )
+    });
+    assert_eq!(new.unwrap(), "short");
+    assert!(!with_capacity.unwrap().is_heap_allocated());
+    assert!(reserve.is_ok());
+
+    // and once allocations succeed again, so do we
+    assert_eq!(CompactString::try_new(LONG).unwrap(), LONG);
+}
+
+#[test]
+fn test_to_string_allocates_once() {
+    // `ToString` goes through our `Display` impl, which writes the whole string at once
+    for text in [
+        "a",
+        "hello",
+        "hello world",
+        "this is a long string that will be heap allocated",
+        "ありがとうございます, and some ascii",
+    ] {
+        for compact in [
+            CompactString::new(text),
+            CompactString::from_static_str(text),
+        ] {
+            let (string, allocations) = count_allocations(|| compact.to_string());
+            assert_eq!(string, text);
+            assert_eq!(allocations, 1, "{:?}", text);
+        }
+    }
+
+    // an empty string doesn't allocate at all
+    let (string, allocations) = count_allocations(|| CompactString::new("").to_string());
+    assert_eq!(string, "");
+    assert_eq!(allocations, 0);
+}
+
+#[test]
+fn test_from_utf8_exact_allocates_once() {
+    // the exact length is reserved up front, so we never have to grow
+    let text = "Здравствуйте, a string that is long enough for the heap 🦀";
+    let (compact, allocations) =
+        count_allocations(|| CompactString::from_utf8_exact(text.bytes()).unwrap());
+    assert_eq!(compact, text);
+    assert_eq!(allocations, 1);
+
+    // short strings are still collected into a buffer once, before they get inlined
+    let text = "hello 🌎, you are nice";
+    let (compact, allocations) =
+        count_allocations(|| CompactString::from_utf8_exact(text.bytes()).unwrap());
+    assert_eq!(compact, text);
+    assert!(!compact.is_heap_allocated());
+    assert_eq!(allocations, 1);
+
+    // and there's nothing to collect for an empty string
+    let (compact, allocations) =
+        count_allocations(|| CompactString::from_utf8_exact("".bytes()).unwrap());
+    assert_eq!(compact, "");
+    assert_eq!(allocations, 0);
+}